@@ -0,0 +1,125 @@
+use crate::character::class::{Category, Class};
+use crate::character::Character;
+use crate::location::Location;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Record of the enemy classes the heroes have met. The details of a class
+/// are only revealed in the bestiary after it's been encountered.
+#[derive(Serialize, Deserialize, Default)]
+pub struct Bestiary {
+    encounters: HashMap<String, Encounter>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Encounter {
+    /// The class as it was first met. Kept here since special enemies,
+    /// like the hero's shadow, aren't part of the class definitions.
+    pub class: Class,
+
+    pub seen: i32,
+    pub beaten: i32,
+
+    /// Range of distances from home where the class was found.
+    pub nearest: i32,
+    pub farthest: i32,
+}
+
+/// A bestiary entry as shown to the player: the encounter is None for
+/// the classes that haven't been met yet.
+pub struct Entry {
+    pub name: String,
+    pub encounter: Option<Encounter>,
+}
+
+impl Bestiary {
+    pub fn met(&mut self, enemy: &Character, location: &Location) {
+        let distance = location.distance_from_home().len();
+        let encounter = self
+            .encounters
+            .entry(enemy.name())
+            .or_insert_with(|| Encounter {
+                class: enemy.class.clone(),
+                seen: 0,
+                beaten: 0,
+                nearest: distance,
+                farthest: distance,
+            });
+        encounter.seen += 1;
+        encounter.nearest = encounter.nearest.min(distance);
+        encounter.farthest = encounter.farthest.max(distance);
+    }
+
+    pub fn beaten(&mut self, enemy: &Character) {
+        if let Some(encounter) = self.encounters.get_mut(&enemy.name()) {
+            encounter.beaten += 1;
+        }
+    }
+
+    /// All the enemy classes ordered by rarity, followed by any special
+    /// enemy that has been met.
+    pub fn entries(&self) -> Vec<Entry> {
+        let mut names: Vec<String> = [Category::Common, Category::Rare, Category::Legendary]
+            .iter()
+            .flat_map(|category| Class::of(category.clone()))
+            .map(|class| class.name.clone())
+            .collect();
+
+        let mut special: Vec<String> = self
+            .encounters
+            .keys()
+            .filter(|name| !names.contains(name))
+            .cloned()
+            .collect();
+        special.sort();
+        names.extend(special);
+
+        names.into_iter().map(|name| self.entry(name)).collect()
+    }
+
+    /// The entry for the given class name, if it's a known enemy.
+    pub fn find(&self, name: &str) -> Option<Entry> {
+        self.entries().into_iter().find(|entry| entry.name == name)
+    }
+
+    fn entry(&self, name: String) -> Entry {
+        let encounter = self.encounters.get(&name).cloned();
+        Entry { name, encounter }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::location::tests::location_from;
+
+    #[test]
+    fn record_encounters() {
+        let mut bestiary = Bestiary::default();
+        let entries = bestiary.entries();
+        assert!(!entries.is_empty());
+        assert!(entries.iter().all(|entry| entry.encounter.is_none()));
+
+        let class = Class::of(Category::Common).first().unwrap().clone();
+        let enemy = Character::new(class, 1);
+        let home = Location::home().path_string();
+        bestiary.met(&enemy, &location_from(&format!("{}/a/b/c", home)));
+        bestiary.met(&enemy, &location_from(&format!("{}/a", home)));
+        bestiary.beaten(&enemy);
+
+        let encounter = bestiary.find(&enemy.name()).unwrap().encounter.unwrap();
+        assert_eq!(2, encounter.seen);
+        assert_eq!(1, encounter.beaten);
+        assert_eq!(1, encounter.nearest);
+        assert_eq!(3, encounter.farthest);
+
+        // special enemies are listed once they're met
+        let mut class = enemy.class.clone();
+        class.name = String::from("shadow");
+        let shadow = Character::new(class, 1);
+        assert!(bestiary.find("shadow").is_none());
+        bestiary.met(&shadow, &Location::home());
+        assert!(bestiary.find("shadow").unwrap().encounter.is_some());
+        assert_eq!(entries.len() + 1, bestiary.entries().len());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::randomizer;
 use crate::randomizer::{random, Randomizer};
 use once_cell::sync::OnceCell;
 use rand::prelude::SliceRandom;
@@ -42,6 +43,21 @@ pub struct Class {
     pub category: Category,
 
     pub inflicts: Option<(super::StatusEffect, u32)>,
+
+    #[serde(default)]
+    pub skills: Vec<super::Skill>,
+
+    /// The element of the class attacks, if any.
+    #[serde(default)]
+    pub element: Option<super::Element>,
+
+    /// Elements that deal double damage to the class.
+    #[serde(default)]
+    pub weaknesses: Vec<super::Element>,
+
+    /// Elements that deal half the damage to the class.
+    #[serde(default)]
+    pub resistances: Vec<super::Element>,
 }
 
 /// Determines whether the class is intended for a Player or, if it's for an enemy,
@@ -86,8 +102,8 @@ impl Class {
     }
 
     pub fn random(category: Category) -> &'static Self {
-        let mut rng = rand::thread_rng();
-        Self::of(category).choose(&mut rng).unwrap()
+        let mut rng = randomizer::rng();
+        Self::of(category).choose(&mut *rng).unwrap()
     }
 
     pub fn names(category: Category) -> HashSet<String> {
@@ -97,7 +113,7 @@ impl Class {
             .collect()
     }
 
-    fn of(category: Category) -> &'static Vec<Class> {
+    pub fn of(category: Category) -> &'static Vec<Class> {
         CLASSES.get_or_init(default_classes).get(&category).unwrap()
     }
 }
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::max;
+use strum_macros::Display;
+
+/// The elemental nature of an attack, which makes it more or less effective
+/// depending on the receiver class.
+#[derive(Serialize, Deserialize, Display, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Element {
+    Fire,
+    Ice,
+    Poison,
+    Holy,
+    Dark,
+}
+
+/// How a character responds to the element of a received attack.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Affinity {
+    Neutral,
+    /// The attack deals double damage.
+    Weak,
+    /// The attack deals half the damage.
+    Resistant,
+}
+
+impl Affinity {
+    pub fn apply(&self, damage: i32) -> i32 {
+        match self {
+            Affinity::Neutral => damage,
+            Affinity::Weak => damage * 2,
+            Affinity::Resistant => max(1, damage / 2),
+        }
+    }
+}
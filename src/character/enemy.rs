@@ -1,7 +1,8 @@
 use super::{class::Category, class::Class, Character};
+use crate::event::{self, GameEvent};
 use crate::item::ring::Ring;
 use crate::location;
-use crate::log;
+use crate::randomizer;
 use crate::randomizer::{random, Randomizer};
 use rand::prelude::SliceRandom;
 use rand::Rng;
@@ -26,7 +27,10 @@ pub fn spawn(location: &location::Location, player: &Character) -> Option<Charac
 
         let level = random().enemy_level(level);
         let enemy = Character::new(class, level);
-        log::enemy_appears(&enemy, location);
+        event::emit(GameEvent::EnemyAppears {
+            enemy: &enemy,
+            location,
+        });
         Some(enemy)
     } else {
         None
@@ -52,8 +56,7 @@ fn spawn_gorthaur(player: &Character, location: &location::Location) -> Option<(
 
 /// Player shadow, appears at home directory
 fn spawn_shadow(player: &Character, location: &location::Location) -> Option<(Class, i32)> {
-    let mut rng = rand::thread_rng();
-    if location.is_home() && rng.gen_ratio(1, 10) {
+    if location.is_home() && randomizer::rng().gen_ratio(1, 10) {
         let mut class = player.class.clone();
         class.name = String::from("shadow");
         class.category = Category::Rare;
@@ -65,9 +68,7 @@ fn spawn_shadow(player: &Character, location: &location::Location) -> Option<(Cl
 
 /// Easter egg, appears at rpg data dir
 fn spawn_dev(player: &Character, location: &location::Location) -> Option<(Class, i32)> {
-    let mut rng = rand::thread_rng();
-
-    if location.is_rpg_dir() && rng.gen_ratio(1, 10) {
+    if location.is_rpg_dir() && randomizer::rng().gen_ratio(1, 10) {
         let mut class = Class::player_first().clone();
         class.name = String::from("dev");
         class.hp.0 /= 2;
@@ -90,8 +91,6 @@ fn spawn_random(player: &Character, distance: &location::Distance) -> (Class, i3
         location::Distance::Far(_) => (0, 8, 2),
     };
 
-    let mut rng = rand::thread_rng();
-
     // assign weights to each group and select one
     let weights = vec![
         (Category::Common, w_common),
@@ -101,7 +100,7 @@ fn spawn_random(player: &Character, distance: &location::Distance) -> (Class, i3
 
     let category = weights
         .as_slice()
-        .choose_weighted(&mut rng, |(_c, weight)| *weight)
+        .choose_weighted(&mut *randomizer::rng(), |(_c, weight)| *weight)
         .unwrap()
         .0
         .clone();
@@ -1,15 +1,22 @@
+use crate::event::{self, GameEvent};
 use crate::item::equipment;
 use crate::item::key::Key;
 use crate::item::ring::Ring;
 use crate::item::Item;
-use crate::log;
 use crate::randomizer::{random, Randomizer};
 use class::Class;
 use serde::{Deserialize, Serialize};
 use std::cmp::{max, min};
 
 pub mod class;
+mod element;
 pub mod enemy;
+mod skill;
+mod status;
+
+pub use element::{Affinity, Element};
+pub use skill::{Action, Skill};
+pub use status::{Effect, Stacking, StatusEffect};
 
 #[derive(Serialize, Deserialize)]
 #[serde(default)]
@@ -33,13 +40,17 @@ pub struct Character {
     pub right_ring: Option<Ring>,
 
     pub status_effect: Option<StatusEffect>,
-}
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
-#[serde(rename_all = "snake_case")]
-pub enum StatusEffect {
-    Burn,
-    Poison,
+    /// Turns left before the status effect wears off, if it has a duration.
+    status_turns: Option<u32>,
+
+    /// Whether the next received attack should deal half the damage.
+    #[serde(skip)]
+    pub guarding: bool,
+
+    /// Whether to attack physically even if there's mp for magic attacks.
+    #[serde(skip)]
+    pub prefer_physical: bool,
 }
 
 /// Outcome of an attack attempt.
@@ -95,6 +106,9 @@ impl Character {
             strength,
             speed,
             status_effect: None,
+            status_turns: None,
+            guarding: false,
+            prefer_physical: false,
         };
 
         for _ in 1..level {
@@ -141,7 +155,10 @@ impl Character {
             }
 
             self.xp = 0;
-            log::change_class(self, lost_xp);
+            event::emit(GameEvent::ClassChanged {
+                player: self,
+                lost_xp,
+            });
             Ok(())
         } else {
             Err(ClassNotFound)
@@ -228,8 +245,7 @@ impl Character {
 
     /// Restore all health and magic points to their max and remove status effects
     pub fn restore(&mut self) -> (i32, i32, bool) {
-        let healed = self.status_effect.is_some();
-        self.status_effect = None;
+        let healed = self.cure();
         (
             self.update_hp(self.max_hp()).unwrap(),
             self.update_mp(self.max_mp()),
@@ -253,7 +269,19 @@ impl Character {
     }
 
     pub fn speed(&self) -> i32 {
-        self.modify_stat(self.speed, Ring::Speed)
+        self.modify_stat(self.status_modified(self.speed, |e| e.speed), Ring::Speed)
+    }
+
+    fn strength(&self) -> i32 {
+        self.status_modified(self.strength, |e| e.strength)
+    }
+
+    /// Apply the percentage modifier of the current status effect, if any,
+    /// to the given stat.
+    fn status_modified(&self, stat: i32, modifier: fn(&Effect) -> i32) -> i32 {
+        self.status_effect.map_or(stat, |status| {
+            stat * (100 + modifier(status.effect())) / 100
+        })
     }
 
     /// Generate and log an attack of this character and apply its effects to
@@ -262,10 +290,41 @@ impl Character {
     /// the receiver died from the inflicted damage.
     pub fn attack(&mut self, receiver: &mut Self) -> (i32, Result<(), Dead>) {
         let (damage, mp_cost) = self.damage(receiver);
-        let damage = random().damage(damage);
+        let inflicted = self.inflicted_status_effect(receiver);
+        self.hit(receiver, damage, mp_cost, inflicted, self.attack_element())
+    }
+
+    /// Like a regular attack but with the damage, mp cost and status effect
+    /// of the given skill.
+    pub fn skill_attack(&mut self, skill: &Skill, receiver: &mut Self) -> (i32, Result<(), Dead>) {
+        let damage = self.skill_damage(skill, receiver);
+        let mp_cost = self.skill_mp_cost(skill);
+        let inflicted = receiver.filter_inflicted(skill.inflicts);
+        self.hit(
+            receiver,
+            damage,
+            mp_cost,
+            inflicted,
+            self.skill_element(skill),
+        )
+    }
+
+    fn hit(
+        &mut self,
+        receiver: &mut Self,
+        damage: i32,
+        mp_cost: i32,
+        inflicted: Option<(StatusEffect, u32)>,
+        element: Option<Element>,
+    ) -> (i32, Result<(), Dead>) {
+        let mut damage = random().damage(damage);
+        if receiver.guarding {
+            damage = max(1, damage / 2);
+            receiver.guarding = false;
+        }
         let xp = self.xp_gained(receiver, damage);
 
-        let attack_type = self.attack_type(receiver);
+        let attack_type = self.attack_type(receiver, inflicted);
         let (damage, xp) = match attack_type {
             AttackType::Regular => (damage, xp),
             AttackType::Critical => (damage * 2, xp),
@@ -278,11 +337,24 @@ impl Character {
         // The receiver can die from the damage. Return the result for
         // the caller to handle that scenario.
         let result = receiver.update_hp(-damage).map(|_| ());
+        if damage > 0
+            && receiver
+                .status_effect
+                .is_some_and(|status| status.effect().cured_by_damage)
+        {
+            receiver.cure();
+        }
         if let AttackType::Effect(status) = attack_type {
-            receiver.status_effect = Some(status);
+            receiver.inflict(status);
         }
 
-        log::attack(receiver, &attack_type, damage, mp_cost);
+        event::emit(GameEvent::Attack {
+            receiver,
+            attack: &attack_type,
+            affinity: receiver.affinity(element),
+            damage,
+            mp_cost,
+        });
 
         (xp, result)
     }
@@ -323,7 +395,13 @@ impl Character {
             Err(Dead) if wearing_revive && !already_revived => {
                 let restored = max(1, self.max_hp() / 10);
                 self.current_hp = restored;
-                log::heal_item(self, "revive", restored, 0, false);
+                event::emit(GameEvent::ItemHeal {
+                    player: self,
+                    item: "revive",
+                    hp: restored,
+                    mp: 0,
+                    healed: false,
+                });
                 Ok(true)
             }
             Err(Dead) => Err(Dead),
@@ -332,10 +410,12 @@ impl Character {
 
     /// Generate a randomized regular/miss/critical/status effect attack based
     /// on the stats of both characters.
-    fn attack_type(&self, receiver: &Self) -> AttackType {
-        let inflicted_status = random().inflicted(self.inflicted_status_effect(receiver));
+    fn attack_type(&self, receiver: &Self, inflicted: Option<(StatusEffect, u32)>) -> AttackType {
+        let inflicted_status = random().inflicted(inflicted);
 
-        if random().is_miss(self.speed(), receiver.speed()) {
+        let status_miss = self.status_effect.map_or(0, |status| status.effect().miss);
+
+        if random().is_miss(self.speed(), receiver.speed()) || random().status_miss(status_miss) {
             AttackType::Miss
         } else if random().is_critical() {
             AttackType::Critical
@@ -350,13 +430,41 @@ impl Character {
     /// deffense.
     /// The second element is the mp cost of the attack, if any.
     pub fn damage(&self, receiver: &Self) -> (i32, i32) {
-        let (damage, mp_cost) = if self.can_magic_attack() {
+        let (damage, mp_cost) = if self.can_magic_attack() && !self.prefer_physical {
             (self.magic_attack(), self.attack_mp_cost())
         } else {
             (self.physical_attack(), 0)
         };
 
-        (max(1, damage - receiver.deffense()), mp_cost)
+        let damage = max(1, damage - receiver.deffense());
+        (
+            receiver.affinity(self.attack_element()).apply(damage),
+            mp_cost,
+        )
+    }
+
+    /// The element of the character's regular attacks: the one of its class
+    /// or, if it has none, the one of an equipped ring.
+    pub fn attack_element(&self) -> Option<Element> {
+        self.class.element.or_else(|| {
+            self.left_ring
+                .as_ref()
+                .and_then(Ring::element)
+                .or_else(|| self.right_ring.as_ref().and_then(Ring::element))
+        })
+    }
+
+    fn skill_element(&self, skill: &Skill) -> Option<Element> {
+        skill.element.or_else(|| self.attack_element())
+    }
+
+    /// How the character class responds to an attack of the given element.
+    pub fn affinity(&self, element: Option<Element>) -> Affinity {
+        match element {
+            Some(element) if self.class.weaknesses.contains(&element) => Affinity::Weak,
+            Some(element) if self.class.resistances.contains(&element) => Affinity::Resistant,
+            _ => Affinity::Neutral,
+        }
     }
 
     /// The character's class enables magic and there's enough mp left
@@ -364,6 +472,38 @@ impl Character {
         self.class.is_magic() && self.current_mp >= self.attack_mp_cost()
     }
 
+    /// The skills of the character class available at its current level.
+    pub fn skills(&self) -> impl Iterator<Item = &Skill> {
+        self.class
+            .skills
+            .iter()
+            .filter(move |skill| skill.level <= self.level)
+    }
+
+    /// The skill is available and there's enough mp left to use it.
+    pub fn can_use(&self, skill: &Skill) -> bool {
+        skill.level <= self.level && self.current_mp >= self.skill_mp_cost(skill)
+    }
+
+    pub fn skill_mp_cost(&self, skill: &Skill) -> i32 {
+        self.class
+            .mp
+            .as_ref()
+            .map_or(0, |mp| mp.at(self.level) * skill.mp_cost / 100)
+    }
+
+    /// The damage of an attack skill, scaled from the character's magic or
+    /// physical attack.
+    pub fn skill_damage(&self, skill: &Skill, receiver: &Self) -> i32 {
+        let base = if self.class.is_magic() {
+            self.magic_attack()
+        } else {
+            self.physical_attack()
+        };
+        let damage = max(1, base * skill.power / 100 - receiver.deffense());
+        receiver.affinity(self.skill_element(skill)).apply(damage)
+    }
+
     fn attack_mp_cost(&self) -> i32 {
         // each magic attack costs one third of the "canonical" mp total for this level
         self.class.mp.as_ref().map_or(0, |mp| mp.at(self.level) / 3)
@@ -373,7 +513,7 @@ impl Character {
     /// its strength and equipment. Magic using characters' strength is dimmed.
     pub fn physical_attack(&self) -> i32 {
         let sword_str = self.sword.as_ref().map_or(0, |s| s.strength());
-        let attack = self.modify_stat(self.strength, Ring::Attack) + sword_str;
+        let attack = self.modify_stat(self.strength(), Ring::Attack) + sword_str;
         if self.class.is_magic() {
             attack / 3
         } else {
@@ -385,7 +525,7 @@ impl Character {
     /// Zero if the current character class is not magic.
     pub fn magic_attack(&self) -> i32 {
         if self.class.is_magic() {
-            let base = self.strength * 3;
+            let base = self.strength() * 3;
             self.modify_stat(base, Ring::Magic)
         } else {
             0
@@ -422,24 +562,55 @@ impl Character {
 
     /// Return the status that this character's attack should inflict on the receiver.
     fn inflicted_status_effect(&self, receiver: &Self) -> Option<(StatusEffect, u32)> {
-        if receiver.left_ring == Some(Ring::Protect) || receiver.right_ring == Some(Ring::Protect) {
-            return None;
-        }
-
         let ring_status = match (self.left_ring.as_ref(), self.right_ring.as_ref()) {
             (Some(Ring::Poison), _) | (_, Some(Ring::Poison)) => Some((StatusEffect::Poison, 3)),
             (Some(Ring::Fire), _) | (_, Some(Ring::Fire)) => Some((StatusEffect::Burn, 3)),
             _ => None,
         };
 
-        let result = self.class.inflicts.or(ring_status);
-        if let Some((status, _)) = result {
-            // don't double-inflict if already has the same status
-            if receiver.status_effect == Some(status) {
-                return None;
-            }
+        receiver.filter_inflicted(self.class.inflicts.or(ring_status))
+    }
+
+    /// Discard the status effect if this character is protected from it.
+    fn filter_inflicted(
+        &self,
+        inflicted: Option<(StatusEffect, u32)>,
+    ) -> Option<(StatusEffect, u32)> {
+        if self.left_ring == Some(Ring::Protect) || self.right_ring == Some(Ring::Protect) {
+            return None;
         }
-        result
+
+        // don't double-inflict if already has the same status, unless it stacks
+        inflicted.filter(|(status, _)| {
+            self.status_effect != Some(*status) || status.effect().stacking != Stacking::Ignore
+        })
+    }
+
+    /// Set the given status effect, replacing the current one. If it's the
+    /// same, its duration is updated according to the stacking rule.
+    pub fn inflict(&mut self, status: StatusEffect) {
+        let effect = status.effect();
+        self.status_turns = match (self.status_effect == Some(status), effect.stacking) {
+            (true, Stacking::Ignore) => self.status_turns,
+            (true, Stacking::Extend) => self
+                .status_turns
+                .zip(effect.duration)
+                .map(|(turns, duration)| turns + duration),
+            _ => effect.duration,
+        };
+        self.status_effect = Some(status);
+    }
+
+    /// Remove the status effect, if any. Returns whether there was one.
+    pub fn cure(&mut self) -> bool {
+        self.status_turns = None;
+        self.status_effect.take().is_some()
+    }
+
+    /// Whether the current status effect prevents the character from acting.
+    pub fn skips_turn(&self) -> bool {
+        self.status_effect
+            .is_some_and(|status| status.effect().skip_turn)
     }
 
     /// If the character has a status condition (e.g. poison) or an equipped
@@ -448,34 +619,52 @@ impl Character {
         let mut hp_effect = 0;
         let mut mp_effect = 0;
 
-        // statuses have a (randomized) +/-5% effect on the base stat
-        let hp_unit = || random().damage(std::cmp::max(1, self.max_hp / 20));
-        let mp_unit = || random().damage(std::cmp::max(1, self.max_mp / 20));
+        // rings have a (randomized) +/-5% effect on the base stat, statuses
+        // the percentage defined for them
+        let hp_unit = |pct: i32| random().damage(max(1, self.max_hp * pct / 100));
+        let mp_unit = |pct: i32| random().damage(max(1, self.max_mp * pct / 100));
 
         if self.left_ring == Some(Ring::RegenHP) || self.right_ring == Some(Ring::RegenHP) {
-            hp_effect += hp_unit();
+            hp_effect += hp_unit(5);
         }
 
         if self.class.is_magic()
             && (self.left_ring == Some(Ring::RegenMP) || self.right_ring == Some(Ring::RegenMP))
         {
-            mp_effect += mp_unit();
+            mp_effect += mp_unit(5);
         }
 
         if self.left_ring == Some(Ring::Ruling) || self.right_ring == Some(Ring::Ruling) {
-            hp_effect -= hp_unit();
+            hp_effect -= hp_unit(5);
         }
 
-        if self.status_effect == Some(StatusEffect::Burn)
-            || self.status_effect == Some(StatusEffect::Poison)
-        {
-            hp_effect -= hp_unit();
+        if let Some(status) = self.status_effect {
+            let effect = status.effect();
+            if effect.hp != 0 {
+                hp_effect += effect.hp.signum() * hp_unit(effect.hp.abs());
+            }
+            if effect.mp != 0 && self.class.is_magic() {
+                mp_effect += effect.mp.signum() * mp_unit(effect.mp.abs());
+            }
         }
 
         let result = self.update_hp(hp_effect).map(|_| ());
         self.update_mp(mp_effect);
 
-        log::status_effect(self, hp_effect, mp_effect);
+        event::emit(GameEvent::StatusEffect {
+            character: self,
+            hp: hp_effect,
+            mp: mp_effect,
+        });
+
+        // count down the turns of effects with a duration
+        match self.status_turns {
+            Some(turns) if turns <= 1 => {
+                self.cure();
+            }
+            Some(turns) => self.status_turns = Some(turns - 1),
+            None => {}
+        }
 
         result
     }
@@ -809,6 +998,93 @@ mod tests {
         assert_eq!(0, hero.current_hp);
     }
 
+    #[test]
+    fn timed_status_effects() {
+        let mut hero = new_char();
+        hero.current_hp = 10;
+
+        // regen heals on every turn until it wears off
+        hero.inflict(StatusEffect::Regen);
+        for _ in 0..5 {
+            hero.apply_status_effects().unwrap();
+        }
+        assert_eq!(15, hero.current_hp);
+        assert!(hero.status_effect.is_none());
+        hero.apply_status_effects().unwrap();
+        assert_eq!(15, hero.current_hp);
+
+        // extended effects add up their duration
+        hero.inflict(StatusEffect::Weak);
+        hero.apply_status_effects().unwrap();
+        assert!(hero
+            .filter_inflicted(Some((StatusEffect::Weak, 2)))
+            .is_some());
+        hero.inflict(StatusEffect::Weak);
+        assert_eq!(Some(9), hero.status_turns);
+
+        // other effects replace the current one, ignored ones don't stack
+        hero.inflict(StatusEffect::Freeze);
+        assert!(hero.skips_turn());
+        hero.apply_status_effects().unwrap();
+        assert!(hero
+            .filter_inflicted(Some((StatusEffect::Freeze, 2)))
+            .is_none());
+        hero.inflict(StatusEffect::Freeze);
+        assert_eq!(Some(1), hero.status_turns);
+        hero.apply_status_effects().unwrap();
+        assert!(!hero.skips_turn());
+
+        assert!(!hero.cure());
+        hero.inflict(StatusEffect::Blind);
+        assert!(hero.cure());
+        assert!(hero.status_turns.is_none());
+    }
+
+    #[test]
+    fn elemental_damage() {
+        let mut hero = new_char();
+        let mut foe = new_char();
+        foe.class.weaknesses = vec![Element::Ice];
+        foe.class.resistances = vec![Element::Fire];
+        assert_eq!(10, hero.damage(&foe).0);
+
+        hero.class.element = Some(Element::Ice);
+        assert_eq!(Affinity::Weak, foe.affinity(hero.attack_element()));
+        assert_eq!(20, hero.damage(&foe).0);
+
+        // rings give an element to classes without one
+        hero.equip_ring(Ring::Fire);
+        assert_eq!(20, hero.damage(&foe).0);
+        hero.class.element = None;
+        assert_eq!(Some(Element::Fire), hero.attack_element());
+        assert_eq!(5, hero.damage(&foe).0);
+
+        // skills can have their own element
+        let fireball = Class::player_by_name("mage").unwrap().skills[0].clone();
+        hero.class.element = Some(Element::Ice);
+        assert_eq!(7, hero.skill_damage(&fireball, &foe));
+
+        let (_, result) = hero.attack(&mut foe);
+        assert!(result.is_ok());
+        assert_eq!(5, foe.current_hp);
+    }
+
+    #[test]
+    fn status_effect_modifiers() {
+        let mut hero = new_char();
+        let mut foe = new_char();
+        assert_eq!(10, hero.physical_attack());
+
+        hero.inflict(StatusEffect::Weak);
+        assert_eq!(7, hero.physical_attack());
+        assert_eq!(10, hero.speed());
+
+        // sleeping characters wake up when hit
+        foe.inflict(StatusEffect::Sleep);
+        let _ = hero.attack(&mut foe);
+        assert!(foe.status_effect.is_none());
+    }
+
     #[test]
     fn apply_ring_status() {
         let mut hero = new_char();
@@ -1192,6 +1468,42 @@ mod tests {
         assert_eq!(7, enemy.current_hp);
     }
 
+    #[test]
+    fn test_skills() {
+        let mut mage = new_plain_stats_char();
+        let mut foe = new_char();
+        foe.max_hp = 100;
+        foe.current_hp = 100;
+
+        // fireball is unlocked at level 5
+        assert_eq!(0, mage.skills().count());
+        mage.level = 5;
+        let fireball = mage.skills().next().unwrap().clone();
+        assert_eq!("fireball", fireball.name);
+
+        // 50% of the canonical mp at level 5
+        assert_eq!(13, mage.skill_mp_cost(&fireball));
+        assert!(!mage.can_use(&fireball));
+        mage.max_mp = 20;
+        mage.current_mp = 20;
+        assert!(mage.can_use(&fireball));
+
+        // 150% of the magic attack
+        assert_eq!(45, mage.skill_damage(&fireball, &foe));
+        let _ = mage.skill_attack(&fireball, &mut foe);
+        assert_eq!(55, foe.current_hp);
+        assert_eq!(7, mage.current_mp);
+
+        // guarding halves the next received attack only
+        let mut hero = new_char();
+        foe.guarding = true;
+        let _ = hero.attack(&mut foe);
+        assert_eq!(50, foe.current_hp);
+        assert!(!foe.guarding);
+        let _ = hero.attack(&mut foe);
+        assert_eq!(40, foe.current_hp);
+    }
+
     #[test]
     fn test_counter() {
         let mut player = new_char();
@@ -1331,6 +1643,10 @@ mod tests {
                 strength: Stat(10, 3),
                 speed: Stat(10, 2),
                 inflicts: None,
+                skills: Vec::new(),
+                element: None,
+                weaknesses: Vec::new(),
+                resistances: Vec::new(),
             },
             1,
         )
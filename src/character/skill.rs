@@ -0,0 +1,55 @@
+use super::{Element, StatusEffect};
+use serde::{Deserialize, Serialize};
+
+/// A special move of a class, used in battle instead of a regular attack
+/// once the character reaches the required level.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Skill {
+    pub name: String,
+    pub action: Action,
+
+    /// The character level at which the skill becomes available.
+    #[serde(default = "one")]
+    pub level: i32,
+
+    /// Percentage of the class mp at the current level consumed on use.
+    #[serde(default)]
+    pub mp_cost: i32,
+
+    /// Percentage of the regular attack damage, or of the enemy gold
+    /// for steal skills.
+    #[serde(default = "hundred")]
+    pub power: i32,
+
+    /// Status effect and its chance (one in N) of being inflicted by an
+    /// attack skill.
+    #[serde(default)]
+    pub inflicts: Option<(StatusEffect, u32)>,
+
+    /// The element of an attack skill. Defaults to the one of the
+    /// character's regular attacks.
+    #[serde(default)]
+    pub element: Option<Element>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Hit the enemy with a scaled version of the regular attack.
+    Attack,
+
+    /// Take some of the gold the enemy would drop, once per battle.
+    Steal,
+
+    /// Halve the damage of the next received attack.
+    Guard,
+}
+
+fn one() -> i32 {
+    1
+}
+
+fn hundred() -> i32 {
+    100
+}
@@ -0,0 +1,136 @@
+use anyhow::{anyhow, Result};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use strum_macros::{Display, EnumIter};
+
+#[derive(Serialize, Deserialize, Display, Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum StatusEffect {
+    Burn,
+    Poison,
+    Freeze,
+    Blind,
+    Weak,
+    Sleep,
+    Regen,
+}
+
+/// How a status effect alters the character suffering it, as defined in
+/// effects.yaml.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Effect {
+    pub emoji: String,
+
+    /// Percentage of the max hp gained (or lost if negative) on every turn.
+    #[serde(default)]
+    pub hp: i32,
+
+    /// Percentage of the max mp gained (or lost if negative) on every turn.
+    #[serde(default)]
+    pub mp: i32,
+
+    /// Percentage increase (or decrease if negative) of the strength.
+    #[serde(default)]
+    pub strength: i32,
+
+    /// Percentage increase (or decrease if negative) of the speed.
+    #[serde(default)]
+    pub speed: i32,
+
+    /// Percentage chance of missing an attack, on top of the regular one.
+    #[serde(default)]
+    pub miss: u32,
+
+    /// Whether the character loses its battle turns.
+    #[serde(default)]
+    pub skip_turn: bool,
+
+    /// Whether receiving damage removes the effect.
+    #[serde(default)]
+    pub cured_by_damage: bool,
+
+    /// Amount of turns the effect lasts. Without one, it lasts until cured.
+    #[serde(default)]
+    pub duration: Option<u32>,
+
+    /// What happens when the effect is inflicted again to a character
+    /// already suffering it.
+    #[serde(default)]
+    pub stacking: Stacking,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Stacking {
+    /// The effect can't be inflicted again until it's gone.
+    #[default]
+    Ignore,
+
+    /// The duration starts over.
+    Refresh,
+
+    /// The duration is added to the remaining turns.
+    Extend,
+}
+
+static EFFECTS: OnceCell<HashMap<StatusEffect, Effect>> = OnceCell::new();
+
+impl StatusEffect {
+    pub fn effect(&self) -> &'static Effect {
+        &EFFECTS.get_or_init(default_effects)[self]
+    }
+
+    /// Customize the effects based on an input yaml byte array. The ones
+    /// it doesn't define keep their default behavior.
+    /// This needs to be called before any effect is looked up.
+    pub fn load(bytes: &[u8]) -> Result<()> {
+        let effects = from_bytes(bytes).map_err(|e| anyhow!("Invalid effects file: {}.", e))?;
+        EFFECTS
+            .set(effects)
+            .map_err(|_| anyhow!("The effects file was loaded after the effects were used."))
+    }
+}
+
+fn default_effects() -> HashMap<StatusEffect, Effect> {
+    serde_yaml::from_slice(include_bytes!("effects.yaml")).unwrap()
+}
+
+fn from_bytes(bytes: &[u8]) -> Result<HashMap<StatusEffect, Effect>, serde_yaml::Error> {
+    let custom: HashMap<StatusEffect, Effect> = serde_yaml::from_slice(bytes)?;
+    let mut effects = default_effects();
+    effects.extend(custom);
+    Ok(effects)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn all_effects_defined() {
+        let effects = default_effects();
+        for status in StatusEffect::iter() {
+            assert!(effects.contains_key(&status), "{} missing", status);
+        }
+        assert!(StatusEffect::Freeze.effect().skip_turn);
+        assert_eq!(Stacking::Ignore, StatusEffect::Burn.effect().stacking);
+    }
+
+    #[test]
+    fn custom_effects() {
+        let effects = from_bytes(b"sleep:\n  emoji: z\n  skip_turn: true\n").unwrap();
+        assert_eq!("z", effects[&StatusEffect::Sleep].emoji);
+        assert!(effects[&StatusEffect::Sleep].duration.is_none());
+        assert!(effects.contains_key(&StatusEffect::Burn));
+
+        assert!(from_bytes(b"sleep:\n  emoji: z\n  turns: 2\n").is_err());
+
+        // the effects in use can't be replaced
+        StatusEffect::Burn.effect();
+        assert!(StatusEffect::load(b"{}").is_err());
+    }
+}
@@ -1,13 +1,17 @@
 use crate::character;
 use crate::character::enemy;
+use crate::config::{self, AttackPreference};
+use crate::datafile;
+use crate::event::{self, GameEvent};
 use crate::game::Game;
 use crate::item;
 use crate::item::key::Key;
+use crate::journal;
 use crate::location::Location;
-use crate::log;
+use crate::quest;
 use anyhow::{anyhow, bail, Result};
 
-use clap::Clap;
+use clap::{ArgEnum, Clap};
 
 #[derive(Clap)]
 pub enum Command {
@@ -51,7 +55,11 @@ pub enum Command {
 
     /// Prints the quest todo list.
     #[clap(alias = "t", display_order = 4)]
-    Todo,
+    Todo {
+        /// Include the locked quests, with the level that unlocks them and their reward.
+        #[clap(long)]
+        all: bool,
+    },
 
     /// Resets the current game.
     Reset {
@@ -64,6 +72,10 @@ pub enum Command {
     /// If name is omitted lists the available character classes.
     Class { name: Option<String> },
 
+    /// Print the hero's battle tactics, or change them with the given options.
+    /// The config file tactics only apply to new games, use --reset to switch to them.
+    Tactics(TacticsOptions),
+
     /// Prints the hero's current location
     #[clap(name = "pwd")]
     PrintWorkDir,
@@ -79,10 +91,133 @@ pub enum Command {
         bribe: bool,
     },
 
+    /// Print the lifetime statistics of all the heroes.
+    Stats,
+
+    /// List the heroes that died in previous runs.
+    Graveyard {
+        /// Sort the heroes by this attribute, highest first.
+        #[clap(long, arg_enum, default_value = "level")]
+        sort: GraveyardOrder,
+    },
+
+    /// List the enemy classes, revealing the details of the ones already met.
+    /// If a name is given, print the stats of that class.
+    Bestiary { name: Option<String> },
+
+    /// Print the journal of past battles, chests and quests.
+    Log {
+        /// How many entries to print.
+        #[clap(long, default_value = "20")]
+        last: usize,
+
+        /// Only print battle entries.
+        #[clap(long, conflicts_with_all = &["chests", "quests"])]
+        battles: bool,
+
+        /// Only print chest and tombstone entries.
+        #[clap(long, conflicts_with = "quests")]
+        chests: bool,
+
+        /// Only print quest entries.
+        #[clap(long)]
+        quests: bool,
+    },
+
+    /// Manage the hero profiles, each with its own separate game data.
+    /// The profile in use is selected with the --profile option.
+    Profile {
+        #[clap(subcommand)]
+        action: ProfileAction,
+    },
+
+    /// Write a portable copy of the hero to the given file, to be imported
+    /// by other users or machines.
+    Export {
+        /// File to export the hero to.
+        #[clap(parse(from_os_str))]
+        file: std::path::PathBuf,
+
+        /// json is human-editable, bin is smaller and faster to load.
+        #[clap(arg_enum, default_value = "json")]
+        data_format: datafile::Format,
+    },
+
+    /// Replace the current game with a hero exported with the export command.
+    /// The replaced game can be recovered with the restore command.
+    Import {
+        /// File to import the hero from.
+        #[clap(parse(from_os_str))]
+        file: std::path::PathBuf,
+    },
+
+    /// Convert the game data file to the given format.
+    Convert {
+        /// json is human-editable, bin is smaller and faster to load.
+        #[clap(arg_enum)]
+        data_format: datafile::Format,
+    },
+
+    /// Replace the game data with one of its automatic backups.
+    Restore {
+        /// Which backup to restore, 1 being the most recent one.
+        #[clap(default_value = "1")]
+        backup: u32,
+    },
+
     #[clap(setting(clap::AppSettings::Hidden))]
     Idkfa { level: i32 },
 }
 
+#[derive(ArgEnum, Clone, Copy)]
+pub enum GraveyardOrder {
+    Level,
+    Gold,
+}
+
+#[derive(Clap)]
+pub struct TacticsOptions {
+    /// Use a potion when the hp falls to this percentage or below. 0 disables it.
+    #[clap(long)]
+    potion_hp: Option<i32>,
+
+    /// Use an ether when the mp falls to this percentage or below. 0 disables it.
+    #[clap(long)]
+    ether_mp: Option<i32>,
+
+    /// Whether to use a remedy when suffering from a status effect.
+    #[clap(long)]
+    remedy: Option<bool>,
+
+    /// Flee with an escape when the hp falls to this percentage or below and
+    /// there are no potions left. 0 disables it.
+    #[clap(long)]
+    escape_hp: Option<i32>,
+
+    /// Whether magic classes should attack with magic or physically.
+    #[clap(long, arg_enum)]
+    attack: Option<AttackPreference>,
+
+    /// Go back to the tactics of the config file before applying the options.
+    #[clap(long)]
+    reset: bool,
+}
+
+#[derive(Clap)]
+pub enum ProfileAction {
+    /// List the existing profiles, marking the one currently in use.
+    List,
+
+    /// Create a new, empty profile.
+    New { name: String },
+
+    /// Create a new profile with a copy of the game data of an existing one.
+    Copy { from: String, to: String },
+
+    /// Delete a profile and all of its game data.
+    Delete { name: String },
+}
+
 pub fn run(cmd: Option<Command>, game: &mut Game) -> Result<()> {
     match cmd.unwrap_or(Command::Stat { items: vec![] }) {
         Command::Stat { items } => stat(game, &items)?,
@@ -94,13 +229,33 @@ pub fn run(cmd: Option<Command>, game: &mut Game) -> Result<()> {
         } => change_dir(game, &destination, run, bribe, force)?,
         Command::Inspect => game.inspect(),
         Command::Class { name } => class(game, &name)?,
+        Command::Tactics(options) => tactics(game, &options)?,
         Command::Battle { run, bribe } => battle(game, run, bribe)?,
-        Command::PrintWorkDir => println!("{}", game.location.path_string()),
+        Command::PrintWorkDir => event::emit(GameEvent::WorkDir {
+            location: &game.location,
+        }),
         Command::Reset { .. } => game.reset(),
         Command::Buy { items } => shop(game, &items)?,
         Command::Use { items } => use_item(game, &items)?,
-        Command::Todo => {
-            log::quest_list(game.quests.list());
+        Command::Todo { all } => {
+            event::emit(GameEvent::QuestList {
+                quests: &game.quests.list(all),
+            });
+        }
+        Command::Stats => event::emit(GameEvent::Stats { stats: &game.stats }),
+        Command::Graveyard { sort } => graveyard(game, sort),
+        Command::Bestiary { name } => bestiary(game, &name)?,
+        Command::Log {
+            last,
+            battles,
+            chests,
+            quests,
+        } => log(last, battles, chests, quests),
+        Command::Export { file, data_format } => datafile::export(game, &file, data_format)?,
+        Command::Convert { data_format } => datafile::convert(data_format),
+        Command::Import { file } => *game = datafile::import(&file)?,
+        Command::Profile { .. } | Command::Restore { .. } => {
+            bail!("This command can't be run on a loaded game.")
         }
         Command::Idkfa { level } => debug_command(game, level),
     };
@@ -148,20 +303,61 @@ fn class(game: &mut Game, class_name: &Option<String>) -> Result<()> {
 
     if let Some(class_name) = class_name {
         let class_name = class_name.to_lowercase();
+        let previous = game.player.name();
         game.player
             .change_class(&class_name)
-            .map_err(|_| anyhow!("Unknown class name."))
+            .map_err(|_| anyhow!("Unknown class name."))?;
+        if game.player.name() != previous {
+            quest::class_changed(game);
+        }
+        Ok(())
     } else {
         let player_classes: Vec<String> =
             character::class::Class::names(character::class::Category::Player)
                 .iter()
                 .cloned()
                 .collect();
-        println!("Options: {}", player_classes.join(", "));
+        event::emit(GameEvent::ClassOptions {
+            classes: &player_classes,
+        });
         Ok(())
     }
 }
 
+/// Change the given tactics options and print the resulting ones.
+fn tactics(game: &mut Game, options: &TacticsOptions) -> Result<()> {
+    let mut tactics = if options.reset {
+        config::get().tactics.clone()
+    } else {
+        game.tactics.clone()
+    };
+
+    if let Some(potion_hp) = options.potion_hp {
+        tactics.potion_hp = potion_hp;
+    }
+    if let Some(ether_mp) = options.ether_mp {
+        tactics.ether_mp = ether_mp;
+    }
+    if let Some(remedy) = options.remedy {
+        tactics.remedy = remedy;
+    }
+    if let Some(escape_hp) = options.escape_hp {
+        tactics.escape_hp = escape_hp;
+    }
+    if let Some(attack) = options.attack {
+        tactics.attack = attack;
+    }
+    tactics
+        .validate()
+        .map_err(|e| anyhow!("Invalid tactics: {}.", e))?;
+
+    game.tactics = tactics;
+    event::emit(GameEvent::Tactics {
+        tactics: &game.tactics,
+    });
+    Ok(())
+}
+
 /// Buy an item from the shop or list the available items if no item name is provided.
 /// Shopping is only allowed when the player is at the home directory.
 fn shop(game: &mut Game, items: &[String]) -> Result<()> {
@@ -178,15 +374,62 @@ fn shop(game: &mut Game, items: &[String]) -> Result<()> {
     }
 }
 
+fn graveyard(game: &Game, sort: GraveyardOrder) {
+    let mut heroes = game.graveyard.heroes().to_vec();
+    match sort {
+        GraveyardOrder::Level => heroes.sort_by_key(|hero| (-hero.level, -hero.gold)),
+        GraveyardOrder::Gold => heroes.sort_by_key(|hero| (-hero.gold, -hero.level)),
+    }
+    event::emit(GameEvent::Graveyard { heroes: &heroes });
+}
+
+fn bestiary(game: &Game, name: &Option<String>) -> Result<()> {
+    if let Some(name) = name {
+        let entry = game
+            .bestiary
+            .find(&name.to_lowercase())
+            .ok_or_else(|| anyhow!("Unknown enemy name."))?;
+        event::emit(GameEvent::Bestiary {
+            entries: &[entry],
+            detailed: true,
+        });
+    } else {
+        event::emit(GameEvent::Bestiary {
+            entries: &game.bestiary.entries(),
+            detailed: false,
+        });
+    }
+    Ok(())
+}
+
+fn log(last: usize, battles: bool, chests: bool, quests: bool) {
+    let kind = if battles {
+        Some(journal::Kind::Battle)
+    } else if chests {
+        Some(journal::Kind::Chest)
+    } else if quests {
+        Some(journal::Kind::Quest)
+    } else {
+        None
+    };
+
+    event::emit(GameEvent::Journal {
+        entries: &journal::last(last, kind),
+    });
+}
+
 fn stat(game: &mut Game, items: &[String]) -> Result<()> {
     if items.is_empty() {
-        log::status(game);
+        event::emit(GameEvent::Status { game });
         Ok(())
     } else {
         for item_name in items {
             let item_name = Key::from(item_name)?;
             let (display, description) = game.describe(item_name)?;
-            println!("{}: {}", display, description);
+            event::emit(GameEvent::ItemDescription {
+                item: &display,
+                description: &description,
+            });
         }
         Ok(())
     }
@@ -195,7 +438,7 @@ fn stat(game: &mut Game, items: &[String]) -> Result<()> {
 /// Use an item from the inventory or list the inventory contents if no item name is provided.
 fn use_item(game: &mut Game, items: &[String]) -> Result<()> {
     if items.is_empty() {
-        println!("{}", log::format_inventory(game));
+        event::emit(GameEvent::Inventory { game });
     } else {
         for item_name in items {
             let item_name = Key::from(item_name)?;
@@ -205,6 +448,23 @@ fn use_item(game: &mut Game, items: &[String]) -> Result<()> {
     Ok(())
 }
 
+/// Run a profile management action. These work directly on the data files
+/// instead of the game, so they are run without loading one.
+pub fn profile(action: &ProfileAction) -> Result<()> {
+    match action {
+        ProfileAction::List => {
+            event::emit(GameEvent::ProfileList {
+                profiles: &datafile::profiles(),
+                current: &datafile::current_profile(),
+            });
+            Ok(())
+        }
+        ProfileAction::New { name } => datafile::create_profile(name),
+        ProfileAction::Copy { from, to } => datafile::copy_profile(from, to),
+        ProfileAction::Delete { name } => datafile::delete_profile(name),
+    }
+}
+
 fn debug_command(game: &mut Game, level: i32) {
     game.reset();
     game.gold = 5000 * level;
@@ -217,6 +477,14 @@ fn debug_command(game: &mut Game, level: i32) {
 mod tests {
     use super::*;
 
+    #[test]
+    fn data_file_commands() {
+        // these are run before loading a game
+        let mut game = Game::new();
+        let cmd = Command::Restore { backup: 1 };
+        assert!(run(Some(cmd), &mut game).is_err());
+    }
+
     #[test]
     fn change_dir_battle() {
         let mut game = Game::new();
@@ -0,0 +1,129 @@
+use anyhow::{anyhow, bail, Result};
+use clap::ArgEnum;
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+
+/// Player preferences read from the config file.
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The tactics assigned to new heroes.
+    pub tactics: Tactics,
+
+    /// Prompt for the hero actions on each battle turn, like the
+    /// --interactive option.
+    pub interactive: bool,
+}
+
+/// Determines how the hero behaves in battle: when to use items instead of
+/// attacking and what kind of attacks to prefer.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Tactics {
+    /// Use a potion when the hp falls to this percentage of the max or below.
+    pub potion_hp: i32,
+
+    /// Use an ether when the mp falls to this percentage of the max or below.
+    /// Magic classes also use them when there's not enough mp left to
+    /// attack with magic.
+    pub ether_mp: i32,
+
+    /// Use a remedy when suffering from a status effect.
+    pub remedy: bool,
+
+    /// Use an escape to flee the battle when the hp falls to this
+    /// percentage of the max or below and there are no potions left.
+    pub escape_hp: i32,
+
+    /// Whether magic classes should attack with magic when they have mp.
+    pub attack: AttackPreference,
+}
+
+#[derive(Serialize, Deserialize, ArgEnum, Display, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum AttackPreference {
+    Magic,
+    Physical,
+}
+
+impl Default for Tactics {
+    fn default() -> Self {
+        Self {
+            potion_hp: 33,
+            ether_mp: 0,
+            remedy: false,
+            escape_hp: 0,
+            attack: AttackPreference::Magic,
+        }
+    }
+}
+
+impl Tactics {
+    /// Verify that the thresholds are valid percentages.
+    pub fn validate(&self) -> Result<()> {
+        for (name, value) in &[
+            ("potion_hp", self.potion_hp),
+            ("ether_mp", self.ether_mp),
+            ("escape_hp", self.escape_hp),
+        ] {
+            if !(0..=100).contains(value) {
+                bail!("{} should be a percentage between 0 and 100", name);
+            }
+        }
+        Ok(())
+    }
+}
+
+static CONFIG: OnceCell<Config> = OnceCell::new();
+
+/// Replace the default preferences with the ones defined in the given
+/// yaml byte array.
+pub fn load(bytes: &[u8]) -> Result<()> {
+    let config: Config =
+        serde_yaml::from_slice(bytes).map_err(|e| anyhow!("Invalid config file: {}.", e))?;
+    config
+        .tactics
+        .validate()
+        .map_err(|e| anyhow!("Invalid config file: {}.", e))?;
+    CONFIG.set(config).unwrap();
+    Ok(())
+}
+
+/// The preferences from the config file, or the default ones if there's none.
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_tactics() {
+        let config: Config = serde_yaml::from_slice(
+            b"
+tactics:
+  potion_hp: 50
+  remedy: true
+  attack: physical
+",
+        )
+        .unwrap();
+        let expected = Tactics {
+            potion_hp: 50,
+            remedy: true,
+            attack: AttackPreference::Physical,
+            ..Tactics::default()
+        };
+        assert_eq!(expected, config.tactics);
+
+        let invalid = Tactics {
+            escape_hp: 120,
+            ..Tactics::default()
+        };
+        assert!(invalid.validate().is_err());
+        assert!(serde_yaml::from_slice::<Config>(b"tactics:\n  potion: 50\n").is_err());
+    }
+}
@@ -0,0 +1,145 @@
+use super::INVALID_DATA;
+use anyhow::{anyhow, Result};
+use bincode::Options;
+use clap::ArgEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+
+/// Binary game files start with this header, followed by the bincode encoded
+/// game data. Anything else is assumed to be JSON.
+const MAGIC: &[u8] = b"RPGBIN";
+
+/// The encodings supported for the game data file. JSON is the default since
+/// it can be inspected and edited by hand, the binary format is smaller and
+/// faster to parse.
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Json,
+    Bin,
+}
+
+impl Format {
+    /// Tell the format of the given game data by looking at its header.
+    pub fn detect(data: &[u8]) -> Self {
+        if data.starts_with(MAGIC) {
+            Format::Bin
+        } else {
+            Format::Json
+        }
+    }
+}
+
+/// Encode the game data, as generated by serde_json, with the given format.
+pub fn encode(value: Value, format: Format) -> Vec<u8> {
+    match format {
+        Format::Json => serde_json::to_vec(&value).unwrap(),
+        Format::Bin => {
+            let mut data = MAGIC.to_vec();
+            data.extend(bincode().serialize(&BinValue::from(value)).unwrap());
+            data
+        }
+    }
+}
+
+/// Decode the game data, detecting its format.
+pub fn decode(data: &[u8]) -> Result<Value> {
+    let value = match Format::detect(data) {
+        Format::Json => serde_json::from_slice(data).ok(),
+        Format::Bin => bincode()
+            .deserialize::<BinValue>(&data[MAGIC.len()..])
+            .ok()
+            .map(Value::from),
+    };
+    value.ok_or_else(|| anyhow!(INVALID_DATA))
+}
+
+fn bincode() -> impl Options {
+    // varint encoding keeps the many small numbers and strings compact
+    bincode::DefaultOptions::new()
+}
+
+/// Some game types, like trait objects serialized with a type tag, can't be
+/// handled by bincode directly since it's not a self-describing format.
+/// Instead of the game struct, the binary format encodes this mirror of the
+/// JSON document, which keeps the same structure and can go through the
+/// same migrations as the JSON files.
+#[derive(Serialize, Deserialize)]
+enum BinValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<BinValue>),
+    Object(Vec<(String, BinValue)>),
+}
+
+impl From<Value> for BinValue {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => BinValue::Null,
+            Value::Bool(b) => BinValue::Bool(b),
+            Value::Number(n) => n
+                .as_i64()
+                .map_or_else(|| BinValue::Float(n.as_f64().unwrap()), BinValue::Int),
+            Value::String(s) => BinValue::String(s),
+            Value::Array(values) => {
+                BinValue::Array(values.into_iter().map(BinValue::from).collect())
+            }
+            Value::Object(map) => BinValue::Object(
+                map.into_iter()
+                    .map(|(key, value)| (key, BinValue::from(value)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl From<BinValue> for Value {
+    fn from(value: BinValue) -> Self {
+        match value {
+            BinValue::Null => Value::Null,
+            BinValue::Bool(b) => Value::Bool(b),
+            BinValue::Int(n) => Value::from(n),
+            BinValue::Float(n) => Number::from_f64(n).map_or(Value::Null, Value::Number),
+            BinValue::String(s) => Value::String(s),
+            BinValue::Array(values) => Value::Array(values.into_iter().map(Value::from).collect()),
+            BinValue::Object(entries) => Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, Value::from(value)))
+                    .collect::<Map<String, Value>>(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+    use crate::item;
+
+    #[test]
+    fn binary_roundtrip() {
+        let mut game = Game::new();
+        game.gold = 100;
+        game.add_item(Box::new(item::Potion::new(1)));
+        game.add_item(Box::new(item::ring::Ring::Void));
+        let value = serde_json::to_value(&game).unwrap();
+
+        let data = encode(value.clone(), Format::Bin);
+        assert_eq!(Format::Bin, Format::detect(&data));
+        assert_eq!(value, decode(&data).unwrap());
+
+        let json = encode(value.clone(), Format::Json);
+        assert_eq!(Format::Json, Format::detect(&json));
+        assert!(data.len() < json.len());
+
+        let game: Game = serde_json::from_value(decode(&data).unwrap()).unwrap();
+        assert_eq!(100, game.gold);
+        assert_eq!(2, game.inventory().len());
+
+        assert!(decode(b"RPGBIN garbage").is_err());
+    }
+}
@@ -0,0 +1,236 @@
+use crate::character::class::Class;
+use crate::item::Item;
+use serde_json::{json, Value};
+
+/// A function that upgrades the game JSON from one schema version to the next.
+type Migration = fn(&mut Value);
+
+/// Migrations ordered by the version they upgrade from: the one at index N
+/// turns a version N game into a version N+1 one.
+/// When making a backward incompatible change to the game data, add a
+/// migration at the end of this list.
+const MIGRATIONS: &[Migration] = &[
+    status_and_equipment_v0,
+    quest_rewards_v1,
+    class_skills_v2,
+    class_elements_v3,
+];
+
+/// The schema version of the game data produced by the current code.
+pub const CURRENT_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// Apply in order all the migrations required to upgrade the given game
+/// data from `version` to the current one.
+pub fn run(game: &mut Value, version: u32) {
+    for migration in MIGRATIONS.iter().skip(version as usize) {
+        migration(game);
+    }
+}
+
+/// Saves from before the schema version was introduced. Some of them may
+/// contain the status effect names and the equipment and item
+/// representations of earlier releases.
+fn status_and_equipment_v0(game: &mut Value) {
+    reindex_inventory(game);
+
+    if let Some(player) = game.get_mut("player") {
+        if let Some(status) = player.get_mut("status_effect") {
+            rename_status(status);
+        }
+        if let Some(status) = player.pointer_mut("/class/inflicts/0") {
+            rename_status(status);
+        }
+        upgrade_equipment(player);
+    }
+
+    if let Some(Value::Object(tombstones)) = game.get_mut("tombstones") {
+        for chest in tombstones.values_mut() {
+            upgrade_equipment(chest);
+        }
+    }
+}
+
+/// Quest rewards used to be just an amount of gold.
+fn quest_rewards_v1(game: &mut Value) {
+    if let Some(Value::Array(quests)) = game.pointer_mut("/quests/quests") {
+        for quest in quests {
+            if let Some(gold) = quest.get(1).filter(|reward| reward.is_number()).cloned() {
+                quest[1] = json!({ "gold": gold });
+            }
+        }
+    }
+}
+
+/// Player classes didn't have skills, take them from the current class
+/// definitions.
+fn class_skills_v2(game: &mut Value) {
+    update_player_class(game, |class, definition| {
+        class["skills"] = json!(definition.skills);
+    });
+}
+
+/// Classes and skills didn't have elements, take the class affinities and
+/// the skills from the current class definitions.
+fn class_elements_v3(game: &mut Value) {
+    update_player_class(game, |class, definition| {
+        class["element"] = json!(definition.element);
+        class["weaknesses"] = json!(definition.weaknesses);
+        class["resistances"] = json!(definition.resistances);
+        class["skills"] = json!(definition.skills);
+    });
+}
+
+/// Apply the given update to the player class, if it's still defined.
+fn update_player_class(game: &mut Value, update: fn(&mut Value, &Class)) {
+    if let Some(class) = game.pointer_mut("/player/class") {
+        let name = class
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        if let Some(definition) = Class::player_by_name(name) {
+            update(class, definition);
+        }
+    }
+}
+
+/// "poisoned" and "burning" were renamed to "poison" and "burn".
+fn rename_status(status: &mut Value) {
+    let renamed = match status.as_str() {
+        Some("poisoned") => "poison",
+        Some("burning") => "burn",
+        _ => return,
+    };
+    *status = Value::from(renamed);
+}
+
+/// The inventory used to be indexed by item names rather than keys, and not
+/// all of those names are valid keys. Index each item again by its own key,
+/// dropping the ones that can't be recognized.
+fn reindex_inventory(game: &mut Value) {
+    if let Some(Value::Object(inventory)) = game.get_mut("inventory") {
+        let mut reindexed = serde_json::Map::new();
+        for item in inventory.values().filter_map(Value::as_array).flatten() {
+            if let Ok(parsed) = serde_json::from_value::<Box<dyn Item>>(item.clone()) {
+                let items = reindexed
+                    .entry(parsed.key().to_string())
+                    .or_insert_with(|| json!([]));
+                items.as_array_mut().unwrap().push(item.clone());
+            }
+        }
+        *inventory = reindexed;
+    }
+}
+
+/// Swords and shields used to be separate structs holding just their level,
+/// they are now a (key, level) tuple.
+fn upgrade_equipment(holder: &mut Value) {
+    for key in &["sword", "shield"] {
+        if let Some(equipment) = holder.get_mut(key) {
+            if let Some(level) = equipment.get("level").cloned() {
+                *equipment = Value::Array(vec![Value::from(*key), level]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::StatusEffect;
+    use crate::game::Game;
+    use crate::item::key::Key;
+    use crate::item::ring::Ring;
+
+    #[test]
+    fn migrate_v0() {
+        let mut game = serde_json::to_value(Game::new()).unwrap();
+        game["player"]["status_effect"] = json!("poisoned");
+        game["player"]["class"]["inflicts"] = json!(["burning", 3]);
+        game["player"]["sword"] = json!({"level": 5});
+        game["tombstones"] =
+            json!({"~/dev": {"items": [], "sword": null, "shield": {"level": 10}, "gold": 100}});
+
+        run(&mut game, 0);
+        let game: Game = serde_json::from_value(game).unwrap();
+
+        assert_eq!(Some(StatusEffect::Poison), game.player.status_effect);
+        assert_eq!(Some((StatusEffect::Burn, 3)), game.player.class.inflicts);
+        assert_eq!(5, game.player.sword.as_ref().unwrap().level());
+        assert!(game.tombstones.contains_key("~/dev"));
+    }
+
+    #[test]
+    fn migrate_v0_inventory() {
+        let mut game = serde_json::to_value(Game::new()).unwrap();
+        game["inventory"] = json!({
+            "Potion": [{"type": "Potion", "level": 1}, {"type": "Potion", "level": 2}],
+            "potion[3]": [{"type": "Potion", "level": 3}],
+            "health stone": [{"type": "Health"}],
+            "fire ring": [{"type": "Ring", "Fire": null}],
+            "elixir": [{"type": "Elixir"}],
+        });
+
+        run(&mut game, 0);
+        let game: Game = serde_json::from_value(game).unwrap();
+        let inventory = game.inventory();
+        assert_eq!(3, inventory.len());
+        assert_eq!(3, inventory[&Key::Potion]);
+        assert_eq!(1, inventory[&Key::HealthStone]);
+        assert_eq!(1, inventory[&Key::Ring(Ring::Fire)]);
+    }
+
+    #[test]
+    fn migrate_v1() {
+        let mut game = serde_json::to_value(Game::new()).unwrap();
+        game["quests"]["quests"][0][1] = json!(100);
+
+        run(&mut game, 1);
+        let game: Game = serde_json::from_value(game).unwrap();
+        let quests = game.quests.list(false);
+        assert_eq!(100, quests[0].reward.gold);
+    }
+
+    #[test]
+    fn migrate_v2() {
+        let mut game = serde_json::to_value(Game::new()).unwrap();
+        game["player"]["class"]
+            .as_object_mut()
+            .unwrap()
+            .remove("skills");
+
+        run(&mut game, 2);
+        let game: Game = serde_json::from_value(game).unwrap();
+        assert_eq!(Class::player_first().skills, game.player.class.skills);
+        assert!(!game.player.class.skills.is_empty());
+    }
+
+    #[test]
+    fn migrate_v3() {
+        let mage = Class::player_by_name("mage").unwrap();
+        let mut game = serde_json::to_value(Game::new()).unwrap();
+        game["player"]["class"] = serde_json::to_value(mage).unwrap();
+        let class = game["player"]["class"].as_object_mut().unwrap();
+        class.remove("element");
+        class["skills"][0]
+            .as_object_mut()
+            .unwrap()
+            .remove("element");
+
+        run(&mut game, 3);
+        let game: Game = serde_json::from_value(game).unwrap();
+        assert_eq!(mage.skills, game.player.class.skills);
+        assert!(game.player.class.skills[0].element.is_some());
+    }
+
+    #[test]
+    fn migrate_current() {
+        // running the migrations over up to date data shouldn't change it
+        let mut game = Game::new();
+        game.player.status_effect = Some(StatusEffect::Burn);
+        let original = serde_json::to_value(&game).unwrap();
+
+        let mut migrated = original.clone();
+        run(&mut migrated, 0);
+        assert_eq!(original, migrated);
+    }
+}
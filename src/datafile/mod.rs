@@ -0,0 +1,700 @@
+use crate::character::{self, class};
+use crate::config;
+use crate::game;
+use crate::journal;
+use crate::quest;
+use anyhow::{anyhow, bail, Result};
+use fs2::FileExt;
+use once_cell::sync::OnceCell;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::io::Write;
+use std::time::{Duration, Instant};
+use std::{fs, io, path, thread};
+
+mod format;
+mod migration;
+mod portable;
+
+pub use format::Format;
+
+// The directories and profile are selected from input args and then act as constants
+static DIRS: OnceCell<Dirs> = OnceCell::new();
+static PROFILE: OnceCell<Option<String>> = OnceCell::new();
+
+// Only set when the game should be saved with a different format than the
+// one it was loaded from
+static FORMAT: OnceCell<Format> = OnceCell::new();
+
+/// Name of the profile that uses the data files at the root of the rpg dir.
+pub const DEFAULT_PROFILE: &str = "default";
+
+struct NotFound;
+
+/// How many previous versions of the game data are kept as backups.
+const BACKUP_COUNT: u32 = 5;
+
+/// Size after which the journal is moved to a backup and a new one started.
+/// Only the previous journal is kept, so this bounds its disk usage.
+const JOURNAL_MAX_SIZE: u64 = 512 * 1024;
+
+const INVALID_DATA: &str = "Invalid game data file. If it was generated with a previous version please run `reset --hard` to restart.";
+
+/// Where the game files are stored: the data dir holds the saved games
+/// and the config dir the optional classes customization and config files.
+#[derive(Debug)]
+struct Dirs {
+    data: path::PathBuf,
+    config: path::PathBuf,
+}
+
+impl Dirs {
+    /// Use a single directory for all the game files, e.g. when set
+    /// explicitly with the RPG_HOME env var.
+    fn at(dir: path::PathBuf) -> Self {
+        Self {
+            data: dir.clone(),
+            config: dir,
+        }
+    }
+
+    /// The XDG base directories ($XDG_DATA_HOME/rpg and $XDG_CONFIG_HOME/rpg)
+    /// or their platform equivalents.
+    fn platform() -> Self {
+        Self {
+            data: dirs::data_dir().unwrap().join("rpg"),
+            config: dirs::config_dir().unwrap().join("rpg"),
+        }
+    }
+
+    /// The directory explicitly requested for the game files, if any, or
+    /// the platform ones otherwise.
+    fn select(data_dir: Option<path::PathBuf>) -> Result<Self, io::Error> {
+        if let Some(dir) = data_dir {
+            Ok(Self::at(std::env::current_dir()?.join(dir)))
+        } else {
+            Ok(Self::migrated(
+                &dirs::home_dir().unwrap().join(".rpg"),
+                Self::platform(),
+            ))
+        }
+    }
+
+    /// The platform directories, moving the files from the ~/.rpg dir used
+    /// by previous versions if necessary. If the legacy dir can't be moved
+    /// it's used as is.
+    fn migrated(legacy: &path::Path, platform: Self) -> Self {
+        if !legacy.exists() || platform.data.exists() {
+            return platform;
+        }
+
+        match migrate(legacy, &platform) {
+            Ok(()) => platform,
+            Err(_) => Self::at(legacy.to_path_buf()),
+        }
+    }
+}
+
+/// Set the data directory and hero profile whose files should be used for this run.
+pub fn init(data_dir: Option<path::PathBuf>, profile: Option<String>) -> Result<()> {
+    if let Some(name) = &profile {
+        validate_profile_name(name)?;
+    }
+
+    DIRS.set(Dirs::select(data_dir)?).unwrap();
+    PROFILE.set(profile).unwrap();
+    Ok(())
+}
+
+/// The game is saved along with the version of its schema, so data
+/// generated by previous versions can be upgraded when loaded.
+#[derive(Serialize)]
+struct Envelope<'a> {
+    version: u32,
+    game: &'a game::Game,
+}
+
+/// Acquire an exclusive lock over the data files of the current profile, so
+/// concurrent commands (e.g. from different shells) don't overwrite each
+/// other's changes. If another process holds the lock, wait for it up to
+/// the given timeout.
+/// The lock is released when the returned file is dropped.
+pub fn lock(timeout: Duration) -> Result<fs::File> {
+    // don't start a new hero because of a typo in the profile name
+    let profile = current_profile();
+    if profile != DEFAULT_PROFILE && !profile_dir().exists() {
+        bail!(
+            "Profile {} not found, create it with `profile new {}`.",
+            profile,
+            profile
+        );
+    }
+
+    fs::create_dir_all(profile_dir())?;
+    lock_file(&profile_dir().join("data.lock"), timeout)
+}
+
+fn lock_file(path: &path::Path, timeout: Duration) -> Result<fs::File> {
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(false)
+        .open(path)?;
+
+    let start = Instant::now();
+    while file.try_lock_exclusive().is_err() {
+        if start.elapsed() >= timeout {
+            bail!("The game data is in use by another rpg-cli command, try again later.");
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    Ok(file)
+}
+
+pub fn load() -> Result<Option<game::Game>> {
+    match read(data_file()) {
+        Err(NotFound) => Ok(None),
+        Ok(data) => {
+            let (game, version) = upgrade(&data)?;
+            if version < migration::CURRENT_VERSION {
+                // keep the outdated file in case the migration goes wrong
+                let backup = profile_dir().join(format!("data.v{}.bak", version));
+                fs::write(backup, data)?;
+            }
+
+            if let Ok(game) = serde_json::from_value(game) {
+                Ok(Some(game))
+            } else {
+                bail!(INVALID_DATA);
+            }
+        }
+    }
+}
+
+/// Save the game with the format of the existing data file (JSON by default),
+/// unless a different one was requested with `convert`.
+pub fn save(game: &game::Game) -> Result<(), io::Error> {
+    let data_file = data_file();
+    let previous = read(data_file.clone()).ok();
+    let format = FORMAT.get().copied().unwrap_or_else(|| {
+        previous
+            .as_ref()
+            .map_or(Format::Json, |data| Format::detect(data))
+    });
+
+    let envelope = Envelope {
+        version: migration::CURRENT_VERSION,
+        game,
+    };
+    let value = serde_json::to_value(&envelope).unwrap();
+
+    // only back up the previous game if it changed, otherwise commands that
+    // just print something would quickly push the older backups out
+    if previous.is_some_and(|data| changed(&data, &value)) {
+        rotate_backups(&data_file)?;
+    }
+    write(data_file, format::encode(value, format))
+}
+
+/// Whether the given data file contents hold a different game than the value.
+fn changed(data: &[u8], value: &Value) -> bool {
+    format::decode(data).map_or(true, |previous| previous != *value)
+}
+
+/// Serialize a set in a stable order. Sets are otherwise iterated in
+/// arbitrary order, making an unchanged game look different on every save.
+pub fn sorted<T: Serialize, S: Serializer>(
+    set: &HashSet<T>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut items: Vec<&T> = set.iter().collect();
+    items.sort_by_cached_key(|item| serde_json::to_string(item).unwrap_or_default());
+    serializer.collect_seq(items)
+}
+
+/// Make the game be saved with the given format from now on.
+pub fn convert(format: Format) {
+    FORMAT.set(format).unwrap();
+}
+
+/// Replace the game data with the given backup, where 1 is the most recent one.
+pub fn restore(backup: u32) -> Result<()> {
+    let data_file = data_file();
+    if let Ok(data) = read(backup_file(&data_file, backup)) {
+        // keep the replaced game as the latest backup so the restore can be undone
+        rotate_backups(&data_file)?;
+        write(data_file, data)?;
+        Ok(())
+    } else {
+        bail!("Backup {} not found.", backup);
+    }
+}
+
+/// Write a portable copy of the game to the given file, with its locations
+/// relative to the home dir so it can be imported by other users or machines.
+pub fn export(game: &game::Game, file: &path::Path, format: Format) -> Result<()> {
+    let envelope = Envelope {
+        version: migration::CURRENT_VERSION,
+        game,
+    };
+    let mut value = serde_json::to_value(&envelope).unwrap();
+    portable::relativize(&mut value["game"]);
+    fs::write(file, format::encode(value, format))?;
+    Ok(())
+}
+
+/// Read a game exported with `export`, placing its locations at the home dir
+/// of the current user.
+pub fn import(file: &path::Path) -> Result<game::Game> {
+    let data =
+        read(file.to_path_buf()).map_err(|_| anyhow!("File {} not found.", file.display()))?;
+    let (mut game, _) = upgrade(&data)?;
+    portable::absolutize(&mut game);
+
+    let game = serde_json::from_value(game).map_err(|_| anyhow!("Invalid game file."))?;
+    portable::validate(&game)?;
+    Ok(game)
+}
+
+/// Parse the game data and migrate it to the current schema version if it
+/// was saved by a previous one. Returns the upgraded game along with the
+/// version it was saved with.
+fn upgrade(data: &[u8]) -> Result<(Value, u32)> {
+    let value = format::decode(data)?;
+
+    // saves from before the envelope was introduced are considered version 0
+    let (version, mut game) = match value {
+        Value::Object(mut envelope) if envelope.contains_key("version") => {
+            let version = envelope["version"].as_u64().unwrap_or_default() as u32;
+            (version, envelope.remove("game").unwrap_or_default())
+        }
+        game => (0, game),
+    };
+
+    if version > migration::CURRENT_VERSION {
+        bail!("The game data file was generated by a newer version of the game.");
+    }
+
+    migration::run(&mut game, version);
+    Ok((game, version))
+}
+
+pub fn remove() -> Result<(), io::Error> {
+    let data_file = data_file();
+    if data_file.exists() {
+        fs::remove_file(data_file)?;
+    }
+    Ok(())
+}
+
+/// Add the given entries at the end of the journal file, one JSON document
+/// per line.
+pub fn append_journal(entries: &[journal::Entry]) -> Result<(), io::Error> {
+    let file = journal_file();
+    if file.metadata().is_ok_and(|m| m.len() > JOURNAL_MAX_SIZE) {
+        fs::rename(&file, backup_file(&file, 1))?;
+    }
+
+    let mut data = Vec::new();
+    for entry in entries {
+        serde_json::to_writer(&mut data, entry)?;
+        data.push(b'\n');
+    }
+
+    fs::create_dir_all(profile_dir())?;
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file)?
+        .write_all(&data)
+}
+
+/// Return the journal entries, oldest first, including the rotated ones.
+/// Lines that can't be parsed are skipped.
+pub fn load_journal() -> Vec<journal::Entry> {
+    let file = journal_file();
+    [backup_file(&file, 1), file]
+        .iter()
+        .filter_map(|file| fs::read_to_string(file).ok())
+        .flat_map(|data| {
+            data.lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Load the classes file of the current profile, falling back to the one
+/// in the config dir when the profile doesn't customize it.
+pub fn load_classes() {
+    let profile_classes = read(profile_dir().join("classes.yaml"));
+    if let Ok(bytes) = profile_classes.or_else(|_| read(classes_file())) {
+        class::Class::load(&bytes)
+    }
+}
+
+/// Load the status effects file of the current profile, falling back to the
+/// one in the config dir.
+pub fn load_effects() -> Result<()> {
+    let profile_effects = read(profile_dir().join("effects.yaml"));
+    if let Ok(bytes) = profile_effects.or_else(|_| read(effects_file())) {
+        character::StatusEffect::load(&bytes)?;
+    }
+    Ok(())
+}
+
+/// Load the quests file of the current profile, falling back to the one
+/// at the root of the data dir.
+pub fn load_quests() -> Result<()> {
+    let profile_quests = read(profile_dir().join("quests.yaml"));
+    if let Ok(bytes) = profile_quests.or_else(|_| read(rpg_dir().join("quests.yaml"))) {
+        quest::load(&bytes)?;
+    }
+    Ok(())
+}
+
+/// Load the config file of the current profile, falling back to the one
+/// in the config dir when the profile doesn't customize it.
+pub fn load_config() -> Result<()> {
+    let profile_config = read(profile_dir().join("config.yaml"));
+    if let Ok(bytes) = profile_config.or_else(|_| read(config_file())) {
+        config::load(&bytes)?;
+    }
+    Ok(())
+}
+
+/// Name of the profile in use.
+pub fn current_profile() -> String {
+    PROFILE
+        .get()
+        .cloned()
+        .flatten()
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+/// List the names of the existing profiles, including the default one.
+pub fn profiles() -> Vec<String> {
+    let mut names = vec![DEFAULT_PROFILE.to_string()];
+    if let Ok(entries) = fs::read_dir(profiles_dir()) {
+        let mut others: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        others.sort();
+        names.extend(others);
+    }
+    names
+}
+
+/// Create an empty profile directory. The game data will be generated
+/// the first time the profile is used.
+pub fn create_profile(name: &str) -> Result<()> {
+    validate_profile_name(name)?;
+    let dir = profile_dir_for(name);
+    if name == DEFAULT_PROFILE || dir.exists() {
+        bail!("Profile {} already exists.", name);
+    }
+    fs::create_dir_all(dir)?;
+    Ok(())
+}
+
+/// Create a new profile with a copy of the data files of an existing one.
+pub fn copy_profile(from: &str, to: &str) -> Result<()> {
+    validate_profile_name(from)?;
+    let source = profile_dir_for(from);
+    if !source.join("data").exists() {
+        bail!("Profile {} has no game data.", from);
+    }
+
+    create_profile(to)?;
+    let dest = profile_dir_for(to);
+    for file in &[
+        "data",
+        "classes.yaml",
+        "effects.yaml",
+        "quests.yaml",
+        "config.yaml",
+    ] {
+        if source.join(file).exists() {
+            fs::copy(source.join(file), dest.join(file))?;
+        }
+    }
+    Ok(())
+}
+
+/// Remove a profile and all of its data files.
+pub fn delete_profile(name: &str) -> Result<()> {
+    validate_profile_name(name)?;
+    if name == DEFAULT_PROFILE {
+        bail!("The default profile can't be deleted, use `reset --hard` instead.");
+    }
+
+    let dir = profile_dir_for(name);
+    if !dir.exists() {
+        bail!("Profile {} not found.", name);
+    }
+    fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+fn validate_profile_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        bail!(
+            "Invalid profile name {}, use only letters, numbers, - and _.",
+            name
+        );
+    }
+    Ok(())
+}
+
+fn read(file: path::PathBuf) -> Result<Vec<u8>, NotFound> {
+    fs::read(file).map_err(|_| NotFound)
+}
+
+/// Write the data to a temporary file and then move it over the given one,
+/// so the file is never left partially written if the process is interrupted.
+fn write(file: path::PathBuf, data: Vec<u8>) -> Result<(), io::Error> {
+    let profile_dir = profile_dir();
+    if !profile_dir.exists() {
+        fs::create_dir_all(&profile_dir)?;
+    }
+
+    let tmp_file = file.with_extension("tmp");
+    let mut tmp = fs::File::create(&tmp_file)?;
+    tmp.write_all(&data)?;
+    tmp.sync_all()?;
+    fs::rename(tmp_file, file)
+}
+
+/// Shift the existing backups of the given file one place, dropping the
+/// oldest, and copy the current file as the most recent one.
+fn rotate_backups(file: &path::Path) -> Result<(), io::Error> {
+    if !file.exists() {
+        return Ok(());
+    }
+
+    for n in (1..BACKUP_COUNT).rev() {
+        let backup = backup_file(file, n);
+        if backup.exists() {
+            fs::rename(backup, backup_file(file, n + 1))?;
+        }
+    }
+    fs::copy(file, backup_file(file, 1))?;
+    Ok(())
+}
+
+fn backup_file(file: &path::Path, n: u32) -> path::PathBuf {
+    file.with_extension(n.to_string())
+}
+
+/// Move the game files from the legacy ~/.rpg dir to the given directories.
+fn migrate(legacy: &path::Path, dirs: &Dirs) -> Result<(), io::Error> {
+    if let Some(parent) = dirs.data.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::rename(legacy, &dirs.data)?;
+
+    let classes = dirs.data.join("classes.yaml");
+    if classes.exists() && dirs.config != dirs.data {
+        fs::create_dir_all(&dirs.config)?;
+        fs::rename(classes, dirs.config.join("classes.yaml"))?;
+    }
+    Ok(())
+}
+
+fn game_dirs() -> &'static Dirs {
+    DIRS.get_or_init(Dirs::platform)
+}
+
+/// The root directory of the game data files.
+pub fn rpg_dir() -> path::PathBuf {
+    game_dirs().data.clone()
+}
+
+fn profiles_dir() -> path::PathBuf {
+    rpg_dir().join("profiles")
+}
+
+/// Directory holding the data files of the given profile.
+/// The default profile is kept at the root of the data dir, for compatibility
+/// with saves from before profiles were introduced.
+fn profile_dir_for(name: &str) -> path::PathBuf {
+    if name == DEFAULT_PROFILE {
+        rpg_dir()
+    } else {
+        profiles_dir().join(name)
+    }
+}
+
+fn profile_dir() -> path::PathBuf {
+    profile_dir_for(&current_profile())
+}
+
+fn data_file() -> path::PathBuf {
+    profile_dir().join("data")
+}
+
+fn journal_file() -> path::PathBuf {
+    profile_dir().join("journal")
+}
+
+fn classes_file() -> path::PathBuf {
+    game_dirs().config.join("classes.yaml")
+}
+
+fn effects_file() -> path::PathBuf {
+    game_dirs().config.join("effects.yaml")
+}
+
+fn config_file() -> path::PathBuf {
+    game_dirs().config.join("config.yaml")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::key;
+    use crate::item::ring;
+
+    #[test]
+    fn serialize_ring() {
+        // rings have a compound enum variant Key::Ring(Ring::_)
+        // that doesn't work with the default enum serialization setup
+        // this verifies the try_from = String workaround
+        let mut game = game::Game::new();
+        game.add_item(Box::new(ring::Ring::Void));
+        let data = serde_json::to_vec(&game).unwrap();
+        let mut game: game::Game = serde_json::from_slice(&data).unwrap();
+        assert!(game.use_item(key::Key::Ring(ring::Ring::Void)).is_ok());
+    }
+
+    #[test]
+    fn backup_rotation() {
+        let dir = std::env::temp_dir().join(format!("rpg-backups-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("data");
+
+        // nothing to back up on the first save
+        rotate_backups(&file).unwrap();
+        assert!(!backup_file(&file, 1).exists());
+
+        for n in 0..BACKUP_COUNT + 2 {
+            fs::write(&file, n.to_string()).unwrap();
+            rotate_backups(&file).unwrap();
+        }
+
+        let latest = BACKUP_COUNT + 1;
+        assert_eq!(
+            latest.to_string(),
+            fs::read_to_string(backup_file(&file, 1)).unwrap()
+        );
+        assert_eq!(
+            (latest + 1 - BACKUP_COUNT).to_string(),
+            fs::read_to_string(backup_file(&file, BACKUP_COUNT)).unwrap()
+        );
+        assert!(!backup_file(&file, BACKUP_COUNT + 1).exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn detect_changes() {
+        let value = serde_json::to_value(game::Game::new()).unwrap();
+        let data = format::encode(value.clone(), Format::Bin);
+
+        // sets are saved in the same order, even after a reload
+        let game: game::Game = serde_json::from_value(value).unwrap();
+        let same = serde_json::to_value(&game).unwrap();
+        assert!(!changed(&data, &same));
+
+        // the order of lists matters
+        let mut reordered = same.clone();
+        let items = reordered["quests"]["quests"].as_array_mut().unwrap();
+        items.swap(0, 1);
+        assert!(changed(&data, &reordered));
+
+        let mut updated = same;
+        updated["gold"] = serde_json::json!(20);
+        assert!(changed(&data, &updated));
+    }
+
+    #[test]
+    fn legacy_dir_migration() {
+        let base = std::env::temp_dir().join(format!("rpg-legacy-{}", std::process::id()));
+        let legacy = base.join(".rpg");
+        fs::create_dir_all(&legacy).unwrap();
+        fs::write(legacy.join("data"), "game").unwrap();
+        fs::write(legacy.join("classes.yaml"), "classes").unwrap();
+        let platform = || Dirs {
+            data: base.join("share").join("rpg"),
+            config: base.join("config").join("rpg"),
+        };
+
+        // the data files are moved to the data dir, the classes to the config one
+        let dirs = Dirs::migrated(&legacy, platform());
+        assert_eq!(platform().data, dirs.data);
+        assert!(!legacy.exists());
+        assert_eq!("game", fs::read_to_string(dirs.data.join("data")).unwrap());
+        assert!(!dirs.data.join("classes.yaml").exists());
+        assert_eq!(
+            "classes",
+            fs::read_to_string(dirs.config.join("classes.yaml")).unwrap()
+        );
+
+        // once migrated, a new legacy dir is left alone
+        fs::create_dir_all(&legacy).unwrap();
+        fs::write(legacy.join("data"), "other game").unwrap();
+        let dirs = Dirs::migrated(&legacy, platform());
+        assert_eq!("game", fs::read_to_string(dirs.data.join("data")).unwrap());
+        assert!(legacy.join("data").exists());
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn data_dir_override() {
+        let dir = std::env::temp_dir().join("rpg-home");
+        let dirs = Dirs::select(Some(dir.clone())).unwrap();
+        assert_eq!(dir, dirs.data);
+        assert_eq!(dir, dirs.config);
+
+        // relative to the working directory
+        let dirs = Dirs::select(Some(path::PathBuf::from("rpg"))).unwrap();
+        assert_eq!(std::env::current_dir().unwrap().join("rpg"), dirs.data);
+        assert_eq!(dirs.data, dirs.config);
+    }
+
+    #[test]
+    fn lock_timeout() {
+        let dir = std::env::temp_dir().join(format!("rpg-lock-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("data.lock");
+        let timeout = Duration::from_millis(100);
+
+        let lock = lock_file(&path, timeout).unwrap();
+        let start = Instant::now();
+        assert!(lock_file(&path, timeout).is_err());
+        assert!(start.elapsed() >= timeout);
+
+        // available again once released
+        drop(lock);
+        assert!(lock_file(&path, timeout).is_ok());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn profile_names() {
+        assert!(validate_profile_name("default").is_ok());
+        assert!(validate_profile_name("team-a_2").is_ok());
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name("../data").is_err());
+        assert!(validate_profile_name("a/b").is_err());
+    }
+}
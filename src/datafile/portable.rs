@@ -0,0 +1,111 @@
+use crate::game::Game;
+use anyhow::{bail, Result};
+use serde_json::Value;
+use std::path;
+
+/// Replace the home dir prefix of the game locations with ~, so they don't
+/// depend on the current user and machine.
+pub fn relativize(game: &mut Value) {
+    let home = dirs::home_dir().unwrap();
+    map_locations(game, |path| {
+        match path::Path::new(path).strip_prefix(&home) {
+            Ok(rest) if rest.as_os_str().is_empty() => String::from("~"),
+            Ok(rest) => format!("~/{}", rest.to_string_lossy()),
+            Err(_) => path.to_string(),
+        }
+    });
+}
+
+/// Expand the ~ prefix of the game locations to the current home dir.
+pub fn absolutize(game: &mut Value) {
+    let home = dirs::home_dir().unwrap();
+    map_locations(game, |path| {
+        if path == "~" {
+            home.to_string_lossy().to_string()
+        } else if let Some(rest) = path.strip_prefix("~/") {
+            home.join(rest).to_string_lossy().to_string()
+        } else {
+            path.to_string()
+        }
+    });
+}
+
+/// Check that the imported game is consistent enough to be played.
+pub fn validate(game: &Game) -> Result<()> {
+    let player = &game.player;
+    if player.level < 1
+        || player.current_hp <= 0
+        || player.current_hp > player.max_hp()
+        || game.gold < 0
+    {
+        bail!("Invalid game file, the hero stats are inconsistent.");
+    }
+    Ok(())
+}
+
+/// Apply the given function to the path of every location in the game.
+/// Tombstones are not included since they are already stored by the
+/// location display name, relative to home.
+fn map_locations(game: &mut Value, f: impl Fn(&str) -> String) {
+    let update = |location: &mut Value| {
+        if let Some(Value::String(path)) = location.get_mut("path") {
+            *path = f(path);
+        }
+    };
+
+    if let Some(location) = game.get_mut("location") {
+        update(location);
+    }
+    if let Some(Value::Array(inspected)) = game.get_mut("inspected") {
+        inspected.iter_mut().for_each(update);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn portable_paths() {
+        let home = dirs::home_dir().unwrap();
+        let home_str = home.to_string_lossy().to_string();
+        let dev_str = home.join("dev").to_string_lossy().to_string();
+
+        let mut game = json!({
+            "location": {"path": dev_str},
+            "inspected": [{"path": home_str}, {"path": "/tmp"}],
+        });
+
+        relativize(&mut game);
+        assert_eq!(
+            json!({
+                "location": {"path": "~/dev"},
+                "inspected": [{"path": "~"}, {"path": "/tmp"}],
+            }),
+            game
+        );
+
+        absolutize(&mut game);
+        assert_eq!(
+            json!({
+                "location": {"path": dev_str},
+                "inspected": [{"path": home_str}, {"path": "/tmp"}],
+            }),
+            game
+        );
+    }
+
+    #[test]
+    fn validate_game() {
+        let mut game = Game::new();
+        assert!(validate(&game).is_ok());
+
+        game.player.current_hp = game.player.max_hp() + 1;
+        assert!(validate(&game).is_err());
+
+        game.player.current_hp = 1;
+        game.gold = -10;
+        assert!(validate(&game).is_err());
+    }
+}
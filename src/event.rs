@@ -0,0 +1,258 @@
+use crate::bestiary;
+use crate::character::{Affinity, AttackType, Character, Skill};
+use crate::config::Tactics;
+use crate::game::Game;
+use crate::graveyard::Fallen;
+use crate::item::key::Key;
+use crate::journal;
+use crate::location::Location;
+use crate::quest;
+use crate::stats::Stats;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// Something that happened during a command and may need to be reported to
+/// the player. The game logic emits these events without knowing how, or if,
+/// they will be displayed; that's up to the registered sinks.
+pub enum GameEvent<'a> {
+    EnemyAppears {
+        enemy: &'a Character,
+        location: &'a Location,
+    },
+    Attack {
+        receiver: &'a Character,
+        attack: &'a AttackType,
+        affinity: Affinity,
+        damage: i32,
+        mp_cost: i32,
+    },
+    StatusEffect {
+        character: &'a Character,
+        hp: i32,
+        mp: i32,
+    },
+    TurnSkipped {
+        character: &'a Character,
+    },
+    Skill {
+        character: &'a Character,
+        skill: &'a Skill,
+        gold: i32,
+    },
+    BattleWon {
+        game: &'a Game,
+        xp: i32,
+        levels_up: i32,
+        gold: i32,
+        items: &'a HashMap<Key, i32>,
+    },
+    BattleLost {
+        player: &'a Character,
+        location: &'a Location,
+    },
+    ChestFound {
+        items: &'a HashMap<Key, i32>,
+        gold: i32,
+        location: &'a Location,
+    },
+    TombstoneFound {
+        items: &'a HashMap<Key, i32>,
+        gold: i32,
+        location: &'a Location,
+    },
+    Bribe {
+        player: &'a Character,
+        amount: i32,
+    },
+    RunAway {
+        player: &'a Character,
+        success: bool,
+    },
+    ItemHeal {
+        player: &'a Character,
+        item: &'a str,
+        hp: i32,
+        mp: i32,
+        healed: bool,
+    },
+    Heal {
+        player: &'a Character,
+        location: &'a Location,
+        hp: i32,
+        mp: i32,
+        healed: bool,
+    },
+    ClassChanged {
+        player: &'a Character,
+        lost_xp: i32,
+    },
+    StatIncrease {
+        player: &'a Character,
+        stat: &'a str,
+        increase: i32,
+    },
+    ShopList {
+        game: &'a Game,
+        items: &'a [(i32, String)],
+    },
+    ShopBuy {
+        cost: i32,
+        items: &'a HashMap<Key, i32>,
+    },
+    QuestList {
+        quests: &'a [quest::Entry],
+    },
+    QuestDone {
+        description: &'a str,
+        reward: &'a quest::Reward,
+    },
+    Status {
+        game: &'a Game,
+    },
+    Inventory {
+        game: &'a Game,
+    },
+    WorkDir {
+        location: &'a Location,
+    },
+    ClassOptions {
+        classes: &'a [String],
+    },
+    ProfileList {
+        profiles: &'a [String],
+        current: &'a str,
+    },
+    Tactics {
+        tactics: &'a Tactics,
+    },
+    ItemDescription {
+        item: &'a str,
+        description: &'a str,
+    },
+    Journal {
+        entries: &'a [journal::Entry],
+    },
+    Stats {
+        stats: &'a Stats,
+    },
+    Graveyard {
+        heroes: &'a [Fallen],
+    },
+    Bestiary {
+        entries: &'a [bestiary::Entry],
+        detailed: bool,
+    },
+    GameSaved {
+        game: &'a Game,
+    },
+}
+
+impl<'a> GameEvent<'a> {
+    /// A short identifier of the event kind, e.g. for structured outputs.
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameEvent::EnemyAppears { .. } => "enemy_appears",
+            GameEvent::Attack { .. } => "attack",
+            GameEvent::StatusEffect { .. } => "status_effect",
+            GameEvent::TurnSkipped { .. } => "turn_skipped",
+            GameEvent::Skill { .. } => "skill",
+            GameEvent::BattleWon { .. } => "battle_won",
+            GameEvent::BattleLost { .. } => "battle_lost",
+            GameEvent::ChestFound { .. } => "chest",
+            GameEvent::TombstoneFound { .. } => "tombstone",
+            GameEvent::Bribe { .. } => "bribe",
+            GameEvent::RunAway { .. } => "run_away",
+            GameEvent::ItemHeal { .. } | GameEvent::Heal { .. } => "heal",
+            GameEvent::ClassChanged { .. } => "change_class",
+            GameEvent::StatIncrease { .. } => "stat_increase",
+            GameEvent::ShopList { .. } => "shop_list",
+            GameEvent::ShopBuy { .. } => "shop_buy",
+            GameEvent::QuestList { .. } => "quest_list",
+            GameEvent::QuestDone { .. } => "quest_done",
+            GameEvent::Status { .. } => "status",
+            GameEvent::Inventory { .. } => "inventory",
+            GameEvent::WorkDir { .. } => "work_dir",
+            GameEvent::ClassOptions { .. } => "class_options",
+            GameEvent::ProfileList { .. } => "profile_list",
+            GameEvent::Tactics { .. } => "tactics",
+            GameEvent::ItemDescription { .. } => "item_description",
+            GameEvent::Journal { .. } => "journal",
+            GameEvent::Stats { .. } => "stats",
+            GameEvent::Graveyard { .. } => "graveyard",
+            GameEvent::Bestiary { .. } => "bestiary",
+            GameEvent::GameSaved { .. } => "game_saved",
+        }
+    }
+}
+
+/// Receives the game events, e.g. to print them or record them somewhere.
+pub trait Sink {
+    fn handle(&mut self, event: &GameEvent);
+
+    /// Called once after the command is done, with its error if it failed.
+    fn finish(&mut self, _error: Option<&anyhow::Error>) {}
+}
+
+// The game runs on a single thread; keeping the sinks per thread prevents
+// parallel tests from seeing each other's events.
+thread_local! {
+    static SINKS: RefCell<Vec<Box<dyn Sink>>> = RefCell::new(Vec::new());
+}
+
+/// Add a sink to receive all the events emitted from now on.
+pub fn register(sink: Box<dyn Sink>) {
+    SINKS.with(|sinks| sinks.borrow_mut().push(sink));
+}
+
+/// Send the event to every registered sink.
+pub fn emit(event: GameEvent) {
+    SINKS.with(|sinks| {
+        for sink in sinks.borrow_mut().iter_mut() {
+            sink.handle(&event);
+        }
+    });
+}
+
+/// Notify the sinks that the command is done.
+pub fn finish(error: Option<&anyhow::Error>) {
+    SINKS.with(|sinks| {
+        for sink in sinks.borrow_mut().iter_mut() {
+            sink.finish(error);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    struct Recorder(Rc<RefCell<Vec<&'static str>>>);
+
+    impl Sink for Recorder {
+        fn handle(&mut self, event: &GameEvent) {
+            self.0.borrow_mut().push(event.name());
+        }
+    }
+
+    #[test]
+    fn emit_to_sinks() {
+        let events = Rc::new(RefCell::new(Vec::new()));
+        register(Box::new(Recorder(events.clone())));
+
+        let game = Game::new();
+        let items = HashMap::new();
+        emit(GameEvent::ChestFound {
+            items: &items,
+            gold: 10,
+            location: &game.location,
+        });
+        emit(GameEvent::QuestDone {
+            description: "a quest",
+            reward: &quest::Reward::gold(100),
+        });
+        emit(GameEvent::Status { game: &game });
+
+        assert_eq!(vec!["chest", "quest_done", "status"], *events.borrow());
+    }
+}
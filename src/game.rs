@@ -1,18 +1,23 @@
 extern crate dirs;
 
+use crate::bestiary::Bestiary;
 use crate::character;
 use crate::character::enemy;
-use crate::character::Character;
+use crate::character::{Action, Character, Skill};
+use crate::config::{self, AttackPreference, Tactics};
+use crate::event::{self, GameEvent};
+use crate::graveyard::Graveyard;
+use crate::input::{self, Choice};
 use crate::item::chest::Chest;
 use crate::item::key::Key;
 use crate::item::ring::Ring;
 use crate::item::Item;
 use crate::location::Location;
-use crate::log;
 use crate::quest;
 use crate::quest::QuestList;
 use crate::randomizer::random;
 use crate::randomizer::Randomizer;
+use crate::stats::Stats;
 use anyhow::{bail, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -32,6 +37,7 @@ pub struct Game {
 
     /// Locations where chest have already been looked for, and therefore
     /// can't be found again.
+    #[serde(serialize_with = "crate::datafile::sorted")]
     inspected: HashSet<Location>,
 
     /// Chests left at the location where the player dies.
@@ -40,9 +46,23 @@ pub struct Game {
     /// There's one instance of each type of ring in the game.
     /// This set starts with all rings and they are moved to the inventory as
     /// they are found in chests.
+    #[serde(serialize_with = "crate::datafile::sorted")]
     pub ring_pool: HashSet<Ring>,
 
+    /// Rings made available at the shop by quest rewards.
+    #[serde(serialize_with = "crate::datafile::sorted")]
+    pub shop_unlocks: HashSet<Key>,
+
     pub quests: QuestList,
+
+    pub stats: Stats,
+
+    pub graveyard: Graveyard,
+
+    pub bestiary: Bestiary,
+
+    /// How the hero behaves in battle.
+    pub tactics: Tactics,
 }
 
 impl Game {
@@ -64,6 +84,11 @@ impl Game {
             inspected: HashSet::new(),
             quests,
             ring_pool,
+            shop_unlocks: HashSet::new(),
+            stats: Stats::default(),
+            graveyard: Graveyard::default(),
+            bestiary: Bestiary::default(),
+            tactics: config::get().tactics.clone(),
         }
     }
 
@@ -71,10 +96,16 @@ impl Game {
     /// Progress is preserved across games.
     pub fn reset(&mut self) {
         let mut new_game = Self::new();
-        // preserve tombstones and quests across hero's lifes
+        // preserve tombstones, quests, shop unlocks, stats, fallen heroes, met enemies
+        // and tactics across hero's lifes
         std::mem::swap(&mut new_game.tombstones, &mut self.tombstones);
         std::mem::swap(&mut new_game.quests, &mut self.quests);
         std::mem::swap(&mut new_game.ring_pool, &mut self.ring_pool);
+        std::mem::swap(&mut new_game.shop_unlocks, &mut self.shop_unlocks);
+        std::mem::swap(&mut new_game.stats, &mut self.stats);
+        std::mem::swap(&mut new_game.graveyard, &mut self.graveyard);
+        std::mem::swap(&mut new_game.bestiary, &mut self.bestiary);
+        std::mem::swap(&mut new_game.tactics, &mut self.tactics);
 
         // remember last selected class
         new_game.player = character::Character::new(self.player.class.clone(), 1);
@@ -110,23 +141,30 @@ impl Game {
     /// Set the hero's location to the one given, and apply related side effects.
     pub fn visit(&mut self, location: Location) -> Result<(), character::Dead> {
         self.location = location;
+        self.stats.visit(&self.location);
+        quest::visit(self);
         if self.location.is_home() {
             let (recovered_hp, recovered_mp, healed) = self.player.restore();
-            log::heal(
-                &self.player,
-                &self.location,
-                recovered_hp,
-                recovered_mp,
+            event::emit(GameEvent::Heal {
+                player: &self.player,
+                location: &self.location,
+                hp: recovered_hp,
+                mp: recovered_mp,
                 healed,
-            );
+            });
         }
 
         // In location is home, already healed of negative status
         let result = self.player.apply_status_effects();
 
         if let Err(character::Dead) = result {
+            let cause = self
+                .player
+                .status_effect
+                .map_or(String::from("unknown"), |status| status.to_string());
+
             // drops tombstone
-            self.battle_lost();
+            self.battle_lost(&cause);
         }
         result
     }
@@ -136,7 +174,11 @@ impl Game {
     pub fn inspect(&mut self) {
         if let Some(mut chest) = self.tombstones.remove(&self.location.to_string()) {
             let (items, gold) = chest.pick_up(self);
-            log::tombstone(&items, gold);
+            event::emit(GameEvent::TombstoneFound {
+                items: &items,
+                gold,
+                location: &self.location,
+            });
             quest::tombstone(self);
         }
 
@@ -144,7 +186,12 @@ impl Game {
             self.inspected.insert(self.location.clone());
             if let Some(mut chest) = Chest::generate(self) {
                 let (items, gold) = chest.pick_up(self);
-                log::chest(&items, gold);
+                self.stats.gold_earned += gold;
+                event::emit(GameEvent::ChestFound {
+                    items: &items,
+                    gold,
+                    location: &self.location,
+                });
                 quest::chest(self);
             }
         }
@@ -177,6 +224,7 @@ impl Game {
             // The effect of using them is unequipping them.
             // This bit of complexity enables a cleaner command api.
             quest::item_used(self, ring.key());
+            quest::ring_unequipped(self, ring.clone());
             self.add_item(Box::new(ring));
             Ok(())
         } else {
@@ -234,37 +282,68 @@ impl Game {
         run: bool,
         bribe: bool,
     ) -> Result<bool, character::Dead> {
+        self.bestiary.met(enemy, &self.location);
+
         // don't attempt bribe and run in the same turn
         if bribe {
-            let bribe_cost = self.player.gold_gained(enemy.level) / 2;
-            if self.gold >= bribe_cost && random().bribe_succeeds() {
-                self.gold -= bribe_cost;
-                log::bribe(&self.player, bribe_cost);
-                return Ok(false);
-            };
-            log::bribe(&self.player, 0);
-        } else if run {
-            let success = random().run_away_succeeds(
-                self.player.level,
-                enemy.level,
-                self.player.speed(),
-                enemy.speed(),
-            );
-            log::run_away(&self.player, success);
-            if success {
+            if self.bribe(enemy) {
                 return Ok(false);
             }
+        } else if run && self.run_away(enemy) {
+            return Ok(false);
         }
 
-        if let Ok(xp) = self.run_battle(enemy) {
-            self.battle_won(enemy, xp);
-            Ok(true)
-        } else {
-            self.battle_lost();
-            Err(character::Dead)
+        self.stats.battles += 1;
+        match self.run_battle(enemy) {
+            Ok(Some(xp)) => {
+                self.battle_won(enemy, xp);
+                Ok(true)
+            }
+            // fled or bribed the enemy mid-battle
+            Ok(None) => Ok(true),
+            Err(character::Dead) => {
+                self.battle_lost(&enemy.name());
+                Err(character::Dead)
+            }
         }
     }
 
+    /// Attempt to bribe the enemy, returning whether it succeeded.
+    fn bribe(&mut self, enemy: &Character) -> bool {
+        let bribe_cost = self.player.gold_gained(enemy.level) / 2;
+        if self.gold >= bribe_cost && random().bribe_succeeds() {
+            self.gold -= bribe_cost;
+            event::emit(GameEvent::Bribe {
+                player: &self.player,
+                amount: bribe_cost,
+            });
+            quest::bribe(self, true);
+            return true;
+        };
+        event::emit(GameEvent::Bribe {
+            player: &self.player,
+            amount: 0,
+        });
+        quest::bribe(self, false);
+        false
+    }
+
+    /// Attempt to run away from the enemy, returning whether it succeeded.
+    fn run_away(&mut self, enemy: &Character) -> bool {
+        let success = random().run_away_succeeds(
+            self.player.level,
+            enemy.level,
+            self.player.speed(),
+            enemy.speed(),
+        );
+        event::emit(GameEvent::RunAway {
+            player: &self.player,
+            success,
+        });
+        quest::run_away(self, success);
+        success
+    }
+
     /// Runs a turn-based combat between the game's player and the given enemy.
     /// The frequency of the turns is determined by the speed stat of each
     /// character.
@@ -272,8 +351,12 @@ impl Game {
     /// Some special abilities are enabled by the player's equipped rings:
     /// Double-beat, counter-attack and revive.
     ///
-    /// Returns Ok(xp gained) if the player wins, or Err(()) if it loses.
-    fn run_battle(&mut self, enemy: &mut Character) -> Result<i32, character::Dead> {
+    /// The player actions are prompted to the user in interactive mode, and
+    /// otherwise chosen according to the game tactics.
+    ///
+    /// Returns Ok(Some(xp gained)) if the player wins, Ok(None) if it flees
+    /// or Err(Dead) if it loses.
+    fn run_battle(&mut self, enemy: &mut Character) -> Result<Option<i32>, character::Dead> {
         // Player's using the revive ring can come back to life at most once per battle
         let mut already_revived = false;
 
@@ -282,18 +365,30 @@ impl Game {
         let (mut pl_accum, mut en_accum) = (0, 0);
         let mut xp = 0;
 
+        // Steal skills can be used once per battle
+        let mut stolen = false;
+        self.player.guarding = false;
+        self.player.prefer_physical = self.tactics.attack == AttackPreference::Physical;
+
         while enemy.current_hp > 0 {
             pl_accum += self.player.speed();
             en_accum += enemy.speed();
 
             if pl_accum >= en_accum {
-                // In some urgent circumstances, it's preferable to use the turn to
-                // recover mp or hp than attacking
-                if !self.autopotion(enemy) && !self.autoether(enemy) {
-                    let (new_xp, _) = self.player.attack(enemy);
-                    xp += new_xp;
-
-                    self.player.maybe_double_beat(enemy);
+                let turn = if self.player.skips_turn() {
+                    event::emit(GameEvent::TurnSkipped {
+                        character: &self.player,
+                    });
+                    Some(0)
+                } else {
+                    match input::battle_turn(self, enemy) {
+                        Some(choice) => self.chosen_turn(choice, enemy),
+                        None => self.auto_turn(enemy, &mut stolen),
+                    }
+                };
+                match turn {
+                    Some(new_xp) => xp += new_xp,
+                    None => return Ok(None),
                 }
 
                 // Status effects are applied after each turn. The player may die
@@ -302,9 +397,17 @@ impl Game {
                 already_revived = self.player.maybe_revive(died, already_revived)?;
 
                 pl_accum = -1;
+            } else if enemy.skips_turn() {
+                event::emit(GameEvent::TurnSkipped { character: enemy });
+                enemy.apply_status_effects().unwrap_or_default();
+                en_accum = -1;
             } else {
+                let status = self.player.status_effect;
                 let (_, died) = enemy.attack(&mut self.player);
                 already_revived = self.player.maybe_revive(died, already_revived)?;
+                if let Some(effect) = self.player.status_effect.filter(|e| status != Some(*e)) {
+                    quest::status_effect(self, effect);
+                }
 
                 self.player.maybe_counter_attack(enemy);
 
@@ -314,22 +417,35 @@ impl Game {
             }
         }
 
-        Ok(xp)
+        Ok(Some(xp))
     }
 
     fn battle_won(&mut self, enemy: &Character, xp: i32) {
         let gold = self.player.gold_gained(enemy.level);
         self.gold += gold;
+        self.stats.battle_won(enemy, gold);
+        self.bestiary.beaten(enemy);
         let levels_up = self.player.add_experience(xp);
 
         let reward_items =
             Chest::battle_loot(self).map_or(HashMap::new(), |mut chest| chest.pick_up(self).0);
 
-        log::battle_won(self, xp, levels_up, gold, &reward_items);
+        event::emit(GameEvent::BattleWon {
+            game: self,
+            xp,
+            levels_up,
+            gold,
+            items: &reward_items,
+        });
         quest::battle_won(self, enemy, levels_up);
     }
 
-    fn battle_lost(&mut self) {
+    /// Record the hero death, caused by the given enemy or status effect.
+    fn battle_lost(&mut self, killer: &str) {
+        self.stats.died(killer);
+        self.graveyard
+            .bury(&self.player, self.gold, killer, &self.location);
+
         // Drop hero items in the location. If there was a previous tombstone
         // merge the contents of both chests
         let mut tombstone = Chest::drop(self);
@@ -339,40 +455,185 @@ impl Game {
         }
         self.tombstones.insert(location, tombstone);
 
-        log::battle_lost(&self.player);
+        event::emit(GameEvent::BattleLost {
+            player: &self.player,
+            location: &self.location,
+        });
+        quest::died(self, killer);
     }
 
-    /// If the player is low on hp and has a potion available use it
-    /// instead of attacking in the current turn.
-    fn autopotion(&mut self, enemy: &Character) -> bool {
-        if self.player.current_hp > self.player.max_hp() / 3 {
-            return false;
+    /// Play the player turn according to the game tactics.
+    /// Returns the gained experience, or None if the player fled the battle.
+    fn auto_turn(&mut self, enemy: &mut Character, stolen: &mut bool) -> Option<i32> {
+        // In some urgent circumstances, it's preferable to use the turn to
+        // recover mp or hp than attacking
+        if self.autoescape(enemy) {
+            return None;
+        }
+        if self.autopotion(enemy) || self.autoether(enemy) || self.autoremedy(enemy) {
+            return Some(0);
         }
 
-        // If there's a good chance of winning the battle on the next attack,
-        // don't use the potion.
-        let (potential_damage, _) = self.player.damage(enemy);
-        if potential_damage >= enemy.current_hp {
+        let xp = if let Some(skill) = self.autoskill(enemy, *stolen) {
+            *stolen |= skill.action == Action::Steal;
+            self.use_skill(&skill, enemy)
+        } else {
+            self.player.attack(enemy).0
+        };
+        self.player.maybe_double_beat(enemy);
+        Some(xp)
+    }
+
+    /// Play the player turn with the action chosen by the user.
+    /// Returns the gained experience, or None if the battle is over without
+    /// a winner.
+    fn chosen_turn(&mut self, choice: Choice, enemy: &mut Character) -> Option<i32> {
+        match choice {
+            Choice::Attack | Choice::Magic => {
+                let prefer_physical = self.player.prefer_physical;
+                self.player.prefer_physical = choice == Choice::Attack;
+                let (xp, _) = self.player.attack(enemy);
+                self.player.maybe_double_beat(enemy);
+                self.player.prefer_physical = prefer_physical;
+                Some(xp)
+            }
+            Choice::Item(key) => {
+                // escapes take the player back home, ending the battle
+                let escape = key == Key::Escape;
+                if self.use_item(key).is_ok() && escape {
+                    return None;
+                }
+                Some(0)
+            }
+            Choice::Run if self.run_away(enemy) => None,
+            Choice::Bribe if self.bribe(enemy) => None,
+            Choice::Run | Choice::Bribe => Some(0),
+        }
+    }
+
+    /// If the player hp is below the tactics threshold and has a potion
+    /// available use it instead of attacking in the current turn.
+    fn autopotion(&mut self, enemy: &Character) -> bool {
+        if !below(
+            self.player.current_hp,
+            self.player.max_hp(),
+            self.tactics.potion_hp,
+        ) || self.can_finish(enemy)
+        {
             return false;
         }
 
         self.use_item(Key::Potion).is_ok()
     }
 
+    /// If the player mp is below the tactics threshold, or it prefers magic
+    /// attacks but doesn't have enough mp left, use an ether if available.
     fn autoether(&mut self, enemy: &Character) -> bool {
-        if !self.player.class.is_magic() || self.player.can_magic_attack() {
+        if !self.player.class.is_magic() {
             return false;
         }
 
-        // If there's a good chance of winning the battle on the next attack,
-        // don't use the ether.
-        let (potential_damage, _) = self.player.damage(enemy);
-        if potential_damage >= enemy.current_hp {
+        let low_mp = below(
+            self.player.current_mp,
+            self.player.max_mp(),
+            self.tactics.ether_mp,
+        );
+        let magic_exhausted = !self.player.prefer_physical && !self.player.can_magic_attack();
+        if !(low_mp || magic_exhausted) || self.can_finish(enemy) {
             return false;
         }
 
         self.use_item(Key::Ether).is_ok()
     }
+
+    /// If the tactics say so, use a remedy to cure the player status effect.
+    fn autoremedy(&mut self, enemy: &Character) -> bool {
+        if !self.tactics.remedy || self.player.status_effect.is_none() || self.can_finish(enemy) {
+            return false;
+        }
+
+        self.use_item(Key::Remedy).is_ok()
+    }
+
+    /// If the player hp is below the tactics threshold and there are no
+    /// potions left, use an escape to flee back home.
+    fn autoescape(&mut self, enemy: &Character) -> bool {
+        if !below(
+            self.player.current_hp,
+            self.player.max_hp(),
+            self.tactics.escape_hp,
+        ) || self.inventory.contains_key(&Key::Potion)
+            || self.can_finish(enemy)
+        {
+            return false;
+        }
+
+        self.use_item(Key::Escape).is_ok()
+    }
+
+    /// If there's a good chance of winning the battle on the next attack,
+    /// it's not worth using items or skills.
+    fn can_finish(&self, enemy: &Character) -> bool {
+        let (potential_damage, _) = self.player.damage(enemy);
+        potential_damage >= enemy.current_hp
+    }
+
+    /// Pick the first of the player's available skills, in the order they
+    /// are declared in the class, that suits the current battle situation.
+    fn autoskill(&self, enemy: &Character, stolen: bool) -> Option<Skill> {
+        if self.can_finish(enemy) {
+            return None;
+        }
+
+        let (enemy_damage, _) = enemy.damage(&self.player);
+        self.player
+            .skills()
+            .filter(|skill| self.player.can_use(skill))
+            .find(|skill| match skill.action {
+                Action::Attack => true,
+                Action::Steal => !stolen,
+                Action::Guard => !self.player.guarding && enemy_damage >= self.player.current_hp,
+            })
+            .cloned()
+    }
+
+    /// Use the given player skill against the enemy.
+    /// Returns the experience gained from it.
+    fn use_skill(&mut self, skill: &Skill, enemy: &mut Character) -> i32 {
+        // attack skills take their mp when hitting the enemy
+        if skill.action != Action::Attack {
+            self.player.current_mp -= self.player.skill_mp_cost(skill);
+        }
+
+        let mut gold = 0;
+        match skill.action {
+            Action::Steal if !random().is_miss(self.player.speed(), enemy.speed()) => {
+                gold = self.player.gold_gained(enemy.level) * skill.power / 100;
+                self.gold += gold;
+                self.stats.gold_earned += gold;
+            }
+            Action::Guard => self.player.guarding = true,
+            _ => {}
+        }
+
+        event::emit(GameEvent::Skill {
+            character: &self.player,
+            skill,
+            gold,
+        });
+
+        if skill.action == Action::Attack {
+            self.player.skill_attack(skill, enemy).0
+        } else {
+            0
+        }
+    }
+}
+
+/// Whether the current value is at or below the given percentage of the
+/// max one. A zero percentage disables the threshold.
+fn below(current: i32, max: i32, percentage: i32) -> bool {
+    percentage > 0 && current <= max * percentage / 100
 }
 
 impl Default for Game {
@@ -491,6 +752,10 @@ mod tests {
             speed: class::Stat(1, 1),
             hp: class::Stat(16, 1),
             strength: class::Stat(5, 1),
+            // element affinities would change the expected damage
+            element: None,
+            weaknesses: vec![],
+            resistances: vec![],
             ..enemy_base.clone()
         };
         let mut enemy = character::Character::new(enemy_class.clone(), 1);
@@ -500,6 +765,9 @@ mod tests {
             speed: class::Stat(2, 1),
             hp: class::Stat(20, 1),
             strength: class::Stat(10, 1), // each hit will take 10hp
+            element: None,
+            weaknesses: vec![],
+            resistances: vec![],
             ..game.player.class.clone()
         };
         game.player = character::Character::new(player_class, 1);
@@ -527,6 +795,209 @@ mod tests {
         assert_eq!(2, game.player.xp);
         // extra 100g for level up quest
         assert_eq!(300, game.gold);
+
+        assert_eq!(2, game.stats.victories);
+        assert_eq!(300, game.stats.gold_earned);
+        assert_eq!(2, game.stats.enemies_beaten[&enemy.name()]);
+    }
+
+    #[test]
+    fn battle_skills() {
+        let enemy_class = class::Class {
+            hp: class::Stat(30, 0),
+            strength: class::Stat(1, 0),
+            speed: class::Stat(1, 0),
+            ..class::Class::random(class::Category::Common).clone()
+        };
+        let mut enemy = character::Character::new(enemy_class, 1);
+
+        let mut game = Game::new();
+        let thief_class = class::Class {
+            hp: class::Stat(100, 0),
+            strength: class::Stat(10, 0),
+            speed: class::Stat(10, 0),
+            ..class::Class::player_by_name("thief").unwrap().clone()
+        };
+        game.player = character::Character::new(thief_class, 5);
+
+        // the first turn is used to steal half the enemy gold,
+        // the next three to beat it
+        let result = game.battle(&mut enemy, false, false);
+        assert!(result.is_ok());
+        // 25g stolen, 50g for the victory, 100g for the completed quest
+        assert_eq!(175, game.gold);
+        assert_eq!(175, game.stats.gold_earned);
+    }
+
+    #[test]
+    fn skills_mp_cost() {
+        let skill = |action| Skill {
+            name: String::from("skill"),
+            action,
+            level: 1,
+            mp_cost: 25,
+            power: 100,
+            inflicts: None,
+            element: None,
+        };
+        let mut game = Game::new();
+        let class = class::Class {
+            mp: Some(class::Stat(40, 0)),
+            skills: vec![skill(Action::Steal), skill(Action::Guard)],
+            ..game.player.class.clone()
+        };
+        game.player = character::Character::new(class, 1);
+        let mut enemy =
+            character::Character::new(class::Class::random(class::Category::Common).clone(), 1);
+
+        game.use_skill(&skill(Action::Steal), &mut enemy);
+        assert_eq!(30, game.player.current_mp);
+        game.use_skill(&skill(Action::Guard), &mut enemy);
+        assert_eq!(20, game.player.current_mp);
+        assert!(game.player.guarding);
+
+        // the skills can't be used without enough mp left
+        game.player.current_mp = 5;
+        assert!(!game.player.can_use(&skill(Action::Steal)));
+        assert!(!game.player.can_use(&skill(Action::Guard)));
+    }
+
+    #[test]
+    fn battle_status_skip() {
+        let enemy_class = class::Class {
+            hp: class::Stat(30, 0),
+            strength: class::Stat(5, 0),
+            speed: class::Stat(10, 0),
+            inflicts: None,
+            ..class::Class::random(class::Category::Common).clone()
+        };
+        let mut enemy = character::Character::new(enemy_class, 1);
+        let mut game = Game::new();
+        let warrior_class = class::Class {
+            hp: class::Stat(100, 0),
+            strength: class::Stat(10, 0),
+            speed: class::Stat(10, 0),
+            ..class::Class::player_by_name("warrior").unwrap().clone()
+        };
+        game.player = character::Character::new(warrior_class, 1);
+
+        // the frozen enemy loses both its turns before being beaten
+        enemy.inflict(character::StatusEffect::Freeze);
+        assert!(game.battle(&mut enemy, false, false).is_ok());
+        assert_eq!(100, game.player.current_hp);
+    }
+
+    #[test]
+    fn tactics_thresholds() {
+        assert!(below(33, 100, 33));
+        assert!(!below(34, 100, 33));
+        assert!(!below(0, 30, 0));
+
+        // a zero threshold never uses the item, even when out of hp or mp
+        let mut game = Game::new();
+        let enemy = character::Character::new(class::Class::player_first().clone(), 1);
+        game.add_item(Box::new(item::Escape::new()));
+        game.tactics.escape_hp = 0;
+        game.player.current_hp = 0;
+        assert!(!game.autoescape(&enemy));
+        game.tactics.escape_hp = 10;
+        assert!(game.autoescape(&enemy));
+    }
+
+    #[test]
+    fn battle_tactics() {
+        let enemy_class = class::Class {
+            hp: class::Stat(30, 0),
+            strength: class::Stat(1, 0),
+            speed: class::Stat(1, 0),
+            ..class::Class::random(class::Category::Common).clone()
+        };
+        let mage_class = class::Class {
+            hp: class::Stat(100, 0),
+            mp: Some(class::Stat(30, 0)),
+            strength: class::Stat(10, 0),
+            speed: class::Stat(10, 0),
+            ..class::Class::player_by_name("mage").unwrap().clone()
+        };
+
+        // cure the status effect and attack physically, saving mp
+        let mut game = Game::new();
+        game.player = character::Character::new(mage_class.clone(), 1);
+        game.player.status_effect = Some(character::StatusEffect::Poison);
+        game.add_item(Box::new(item::Remedy::new()));
+        game.tactics.remedy = true;
+        game.tactics.attack = AttackPreference::Physical;
+
+        let mut enemy = character::Character::new(enemy_class.clone(), 1);
+        assert!(game.battle(&mut enemy, false, false).is_ok());
+        assert!(game.player.status_effect.is_none());
+        assert!(!game.inventory().contains_key(&Key::Remedy));
+        assert_eq!(30, game.player.current_mp);
+
+        // flee when low on hp and out of potions
+        let mut game = Game::new();
+        game.player = character::Character::new(mage_class, 1);
+        game.player.current_hp = 20;
+        game.add_item(Box::new(item::Escape::new()));
+        game.tactics.escape_hp = 25;
+
+        let mut enemy = character::Character::new(enemy_class, 1);
+        enemy.current_hp = 100;
+        assert!(game.battle(&mut enemy, false, false).is_ok());
+        assert!(!game.inventory().contains_key(&Key::Escape));
+        assert_eq!(100, enemy.current_hp);
+        assert_eq!(0, game.stats.victories);
+    }
+
+    struct Script(Vec<Choice>);
+
+    impl input::Input for Script {
+        fn battle_turn(&mut self, _game: &Game, _enemy: &Character) -> Option<Choice> {
+            if self.0.is_empty() {
+                None
+            } else {
+                Some(self.0.remove(0))
+            }
+        }
+    }
+
+    #[test]
+    fn battle_interactive() {
+        let enemy_class = class::Class {
+            hp: class::Stat(30, 0),
+            strength: class::Stat(1, 0),
+            speed: class::Stat(1, 0),
+            ..class::Class::random(class::Category::Common).clone()
+        };
+        let mage_class = class::Class {
+            hp: class::Stat(100, 0),
+            mp: Some(class::Stat(30, 0)),
+            strength: class::Stat(10, 0),
+            speed: class::Stat(10, 0),
+            ..class::Class::player_by_name("mage").unwrap().clone()
+        };
+        let mut game = Game::new();
+        game.player = character::Character::new(mage_class, 1);
+        game.player.current_hp = 50;
+        game.add_item(Box::new(item::Potion::new(1)));
+        let mut enemy = character::Character::new(enemy_class, 1);
+
+        // the bribe fails in tests, then a weak physical attack and a
+        // magic one finish the enemy
+        input::set(Box::new(Script(vec![
+            Choice::Item(Key::Potion),
+            Choice::Bribe,
+            Choice::Attack,
+            Choice::Magic,
+        ])));
+        let result = game.battle(&mut enemy, false, false);
+        input::set(Box::new(Script(Vec::new())));
+
+        assert!(result.is_ok());
+        assert_eq!(1, game.stats.victories);
+        assert_eq!(75, game.player.current_hp);
+        assert_eq!(20, game.player.current_mp);
+        assert!(!game.inventory().contains_key(&Key::Potion));
     }
 
     #[test]
@@ -536,5 +1007,14 @@ mod tests {
         let mut enemy = character::Character::new(enemy_class.clone(), 10);
         let result = game.battle(&mut enemy, false, false);
         assert!(result.is_err());
+        assert_eq!(1, game.stats.deaths);
+        assert_eq!(1, game.stats.death_causes[&enemy.name()]);
+        assert_eq!(1, game.graveyard.heroes().len());
+        assert_eq!(enemy.name(), game.graveyard.heroes()[0].killer);
+        assert_eq!(1, game.graveyard.heroes()[0].level);
+
+        // stats are kept for the next hero
+        game.reset();
+        assert_eq!(1, game.stats.battles);
     }
 }
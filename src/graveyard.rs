@@ -0,0 +1,71 @@
+use crate::character::Character;
+use crate::location::Location;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// How many fallen heroes are remembered. The graveyard is part of the
+/// game data, so older ones are forgotten to keep it from growing forever.
+const CAPACITY: usize = 100;
+
+/// Hall of fame of the heroes that died in previous runs.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(transparent)]
+pub struct Graveyard {
+    heroes: Vec<Fallen>,
+}
+
+/// A hall of fame record of a hero that died.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Fallen {
+    pub class: String,
+    pub level: i32,
+    pub gold: i32,
+
+    /// The enemy class or status effect that killed the hero.
+    pub killer: String,
+
+    pub location: String,
+    pub time: DateTime<Local>,
+}
+
+impl Graveyard {
+    /// Record the death of the hero, forgetting the oldest one if the
+    /// graveyard is full.
+    pub fn bury(&mut self, player: &Character, gold: i32, killer: &str, location: &Location) {
+        self.heroes.push(Fallen {
+            class: player.name(),
+            level: player.level,
+            gold,
+            killer: killer.to_string(),
+            location: location.to_string(),
+            time: Local::now(),
+        });
+        if self.heroes.len() > CAPACITY {
+            self.heroes.remove(0);
+        }
+    }
+
+    pub fn heroes(&self) -> &[Fallen] {
+        &self.heroes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::class::Class;
+
+    #[test]
+    fn capacity() {
+        let mut graveyard = Graveyard::default();
+        let mut player = Character::new(Class::player_first().clone(), 1);
+        let home = Location::home();
+
+        for level in 1..=CAPACITY as i32 + 5 {
+            player.level = level;
+            graveyard.bury(&player, 0, "rat", &home);
+        }
+        assert_eq!(CAPACITY, graveyard.heroes().len());
+        assert_eq!(6, graveyard.heroes()[0].level);
+    }
+}
@@ -0,0 +1,115 @@
+use crate::character::Character;
+use crate::game::Game;
+use crate::item::key::Key;
+use anyhow::{anyhow, bail, Result};
+use std::cell::RefCell;
+use std::io::{self, BufRead, Write};
+
+/// An action chosen by the player for its battle turn.
+#[derive(Debug, PartialEq)]
+pub enum Choice {
+    Attack,
+    Magic,
+    Item(Key),
+    Run,
+    Bribe,
+}
+
+/// Decides the player actions in battle, e.g. by asking the user.
+pub trait Input {
+    /// Return the action for the player turn, or None to leave it to the
+    /// battle tactics.
+    fn battle_turn(&mut self, game: &Game, enemy: &Character) -> Option<Choice>;
+}
+
+// Like the event sinks, the input is kept per thread so parallel tests
+// can script their own.
+thread_local! {
+    static INPUT: RefCell<Option<Box<dyn Input>>> = RefCell::new(None);
+}
+
+/// Use the given input to decide the player battle turns from now on.
+pub fn set(input: Box<dyn Input>) {
+    INPUT.with(|current| *current.borrow_mut() = Some(input));
+}
+
+/// Ask the registered input, if any, for the action of the player turn.
+pub fn battle_turn(game: &Game, enemy: &Character) -> Option<Choice> {
+    INPUT.with(|input| {
+        input
+            .borrow_mut()
+            .as_mut()
+            .and_then(|input| input.battle_turn(game, enemy))
+    })
+}
+
+/// Prompts the user for each battle turn through stdin.
+pub struct Terminal;
+
+impl Input for Terminal {
+    fn battle_turn(&mut self, game: &Game, _enemy: &Character) -> Option<Choice> {
+        let stdin = io::stdin();
+        loop {
+            print!("attack, magic, item <name>, run or bribe? ");
+            io::stdout().flush().ok()?;
+
+            // an empty line or the end of the input leave the turn to the tactics
+            let mut line = String::new();
+            if stdin.lock().read_line(&mut line).ok()? == 0 || line.trim().is_empty() {
+                return None;
+            }
+
+            match parse(&line).and_then(|choice| validate(choice, game)) {
+                Ok(choice) => return Some(choice),
+                Err(err) => println!("{}", err),
+            }
+        }
+    }
+}
+
+fn parse(line: &str) -> Result<Choice> {
+    let mut words = line.split_whitespace();
+    let choice = match words.next().unwrap_or_default() {
+        "attack" | "a" => Choice::Attack,
+        "magic" | "m" => Choice::Magic,
+        "item" | "i" => {
+            let name = words.next().ok_or_else(|| anyhow!("Missing item name."))?;
+            Choice::Item(Key::from(name)?)
+        }
+        "run" | "r" => Choice::Run,
+        "bribe" | "b" => Choice::Bribe,
+        other => bail!("Unknown action {}.", other),
+    };
+    Ok(choice)
+}
+
+/// Reject the choices the player can't make in the current game state.
+fn validate(choice: Choice, game: &Game) -> Result<Choice> {
+    match &choice {
+        Choice::Magic if !game.player.can_magic_attack() => {
+            bail!("Not enough mp for a magic attack.")
+        }
+        Choice::Item(key) if !game.inventory().contains_key(key) => bail!("Item not found."),
+        _ => Ok(choice),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_choices() {
+        assert_eq!(Choice::Attack, parse("a\n").unwrap());
+        assert_eq!(Choice::Magic, parse("magic").unwrap());
+        assert_eq!(Choice::Item(Key::Potion), parse("item potion").unwrap());
+        assert_eq!(Choice::Bribe, parse(" b ").unwrap());
+        assert!(parse("item").is_err());
+        assert!(parse("dance").is_err());
+
+        let game = Game::new();
+        assert!(validate(Choice::Magic, &game).is_err());
+        assert!(validate(Choice::Item(Key::Potion), &game).is_err());
+        assert!(validate(Choice::Run, &game).is_ok());
+    }
+}
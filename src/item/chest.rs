@@ -4,9 +4,10 @@ use super::ring;
 use super::stone;
 use super::{Escape, Ether, Item, Potion, Remedy};
 use crate::game;
+use crate::randomizer;
 use crate::randomizer::random;
 use crate::randomizer::Randomizer;
-use rand::prelude::{IteratorRandom, SliceRandom};
+use rand::prelude::SliceRandom;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -177,7 +178,7 @@ fn maybe_upgrade(current: &mut Option<Equipment>, other: &mut Option<Equipment>)
 }
 
 fn random_equipment(distance: i32) -> (Option<Equipment>, Option<Equipment>) {
-    let mut rng = rand::thread_rng();
+    let mut rng = randomizer::rng();
 
     let level = std::cmp::max(1, (distance / 5) * 5);
 
@@ -188,7 +189,7 @@ fn random_equipment(distance: i32) -> (Option<Equipment>, Option<Equipment>) {
         (20, (None, Some(Equipment::shield(level + 5)))),
         (1, (Some(Equipment::sword(100)), None)),
     ]
-    .choose_weighted_mut(&mut rng, |c| c.0)
+    .choose_weighted_mut(&mut *rng, |c| c.0)
     .unwrap()
     .to_owned()
     .1
@@ -212,17 +213,22 @@ fn random_item(level: i32) -> Box<dyn Item> {
     // with the resulting index
     let indexed_weights: Vec<_> = choices.iter().map(|(w, _)| w).enumerate().collect();
 
-    let mut rng = rand::thread_rng();
+    let mut rng = randomizer::rng();
     let index = indexed_weights
-        .choose_weighted(&mut rng, |c| c.1)
+        .choose_weighted(&mut *rng, |c| c.1)
         .unwrap()
         .0;
     choices.remove(index).1
 }
 
 fn random_ring(game: &mut game::Game) -> Option<ring::Ring> {
-    let mut rng = rand::thread_rng();
-    if let Some(ring) = game.ring_pool.iter().choose(&mut rng).cloned() {
+    // sort the pool before choosing, otherwise the set iteration order
+    // would make the outcome differ across runs with the same seed
+    let mut rings: Vec<&ring::Ring> = game.ring_pool.iter().collect();
+    rings.sort_by_key(|ring| ring.to_string());
+
+    let mut rng = randomizer::rng();
+    if let Some(ring) = rings.choose(&mut *rng).cloned().cloned() {
         game.ring_pool.take(&ring)
     } else {
         None
@@ -1,9 +1,9 @@
 use core::fmt;
 
 use crate::character::class as character;
+use crate::event::{self, GameEvent};
 use crate::game;
 use crate::location;
-use crate::log;
 use serde::{Deserialize, Serialize};
 
 pub mod chest;
@@ -45,7 +45,13 @@ impl fmt::Display for Potion {
 impl Item for Potion {
     fn apply(&mut self, game: &mut game::Game) {
         let recovered = game.player.update_hp(self.restores()).unwrap();
-        log::heal_item(&game.player, "potion", recovered, 0, false);
+        event::emit(GameEvent::ItemHeal {
+            player: &game.player,
+            item: "potion",
+            hp: recovered,
+            mp: 0,
+            healed: false,
+        });
     }
 
     fn key(&self) -> key::Key {
@@ -99,8 +105,14 @@ impl Remedy {
 #[typetag::serde]
 impl Item for Remedy {
     fn apply(&mut self, game: &mut game::Game) {
-        let healed = game.player.status_effect.take().is_some();
-        log::heal_item(&game.player, "remedy", 0, 0, healed);
+        let healed = game.player.cure();
+        event::emit(GameEvent::ItemHeal {
+            player: &game.player,
+            item: "remedy",
+            hp: 0,
+            mp: 0,
+            healed,
+        });
     }
 
     fn key(&self) -> key::Key {
@@ -146,7 +158,13 @@ impl Item for Ether {
             .map_or(0, |mp| mp.at(self.level));
         let recovered_mp = game.player.update_mp(to_restore);
 
-        log::heal_item(&game.player, "ether", 0, recovered_mp, false);
+        event::emit(GameEvent::ItemHeal {
+            player: &game.player,
+            item: "ether",
+            hp: 0,
+            mp: recovered_mp,
+            healed: false,
+        });
     }
 
     fn key(&self) -> key::Key {
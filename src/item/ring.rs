@@ -1,5 +1,7 @@
 use super::{key, Item};
+use crate::character::Element;
 use crate::game;
+use crate::quest;
 use core::fmt;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -50,6 +52,15 @@ impl Ring {
             _ => 0.0,
         }
     }
+
+    /// The element given to the wearer's attacks, if any.
+    pub fn element(&self) -> Option<Element> {
+        match self {
+            Ring::Fire => Some(Element::Fire),
+            Ring::Poison => Some(Element::Poison),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for Ring {
@@ -64,8 +75,10 @@ impl Item for Ring {
     /// wearing two rings, move the second one back to the inventory.
     fn apply(&mut self, game: &mut game::Game) {
         if let Some(removed) = game.player.equip_ring(self.clone()) {
+            quest::ring_unequipped(game, removed.clone());
             game.add_item(Box::new(removed));
         }
+        quest::ring_equipped(game, self.clone());
     }
 
     fn key(&self) -> key::Key {
@@ -86,8 +99,8 @@ impl Item for Ring {
             Ring::RegenMP => "recovers mp on every turn",
             Ring::Ruling => "one ring to rule them all",
             Ring::Protect => "prevents status ailments",
-            Ring::Fire => "inflicts burn status on attack",
-            Ring::Poison => "inflicts poison status on attack",
+            Ring::Fire => "fire attacks that may inflict burn status",
+            Ring::Poison => "poison attacks that may inflict poison status",
             Ring::Double => "strike twice per turn",
             Ring::Counter => "counter-attack when an attack is received",
             Ring::Revive => "come back from dead during battle",
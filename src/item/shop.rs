@@ -4,9 +4,8 @@ use super::equipment::Equipment;
 use super::key::Key;
 use super::ring::Ring;
 use super::Item;
-use crate::character::Character;
+use crate::event::{self, GameEvent};
 use crate::game::Game;
-use crate::log;
 use crate::quest;
 use anyhow::{bail, Result};
 use std::collections::HashMap;
@@ -17,11 +16,14 @@ pub fn list(game: &Game) -> Result<()> {
         bail!("Shop is only allowed at home.");
     }
 
-    let items = available_items(&game.player)
+    let items = available_items(game)
         .iter()
         .map(|s| (s.cost(), s.to_string()))
-        .collect();
-    log::shop_list(game, items);
+        .collect::<Vec<_>>();
+    event::emit(GameEvent::ShopList {
+        game,
+        items: &items,
+    });
     Ok(())
 }
 
@@ -41,7 +43,7 @@ pub fn buy(game: &mut Game, item_keys: &[Key]) -> Result<()> {
     // Buy one at a time and break on first error
     for key in item_keys {
         // get list every time to prevent e.g. buying the sword twice
-        let item = available_items(&game.player)
+        let item = available_items(game)
             .into_iter()
             .find(|s| s.to_key() == *key);
 
@@ -65,7 +67,10 @@ pub fn buy(game: &mut Game, item_keys: &[Key]) -> Result<()> {
     }
 
     // log what could be bought even if there was an error
-    log::shop_buy(total_cost, &item_counts);
+    event::emit(GameEvent::ShopBuy {
+        cost: total_cost,
+        items: &item_counts,
+    });
     if !error.is_empty() {
         bail!(error);
     }
@@ -73,8 +78,9 @@ pub fn buy(game: &mut Game, item_keys: &[Key]) -> Result<()> {
 }
 
 /// Build a list of items currently available at the shop
-fn available_items(player: &Character) -> Vec<Box<dyn Shoppable>> {
+fn available_items(game: &Game) -> Vec<Box<dyn Shoppable>> {
     let mut items = Vec::<Box<dyn Shoppable>>::new();
+    let player = &game.player;
     let level = player.rounded_level();
 
     let sword = Equipment::sword(level);
@@ -99,10 +105,21 @@ fn available_items(player: &Character) -> Vec<Box<dyn Shoppable>> {
     let escape = super::Escape::new();
     items.push(Box::new(escape));
 
-    if player.level >= 25 {
+    if player.level >= 25 || game.shop_unlocks.contains(&Key::Ring(Ring::Diamond)) {
         items.push(Box::new(Ring::Diamond));
     }
 
+    // rings unlocked by quests can be bought while they're not found
+    let mut unlocked: Vec<&Ring> = game
+        .ring_pool
+        .iter()
+        .filter(|ring| game.shop_unlocks.contains(&ring.key()))
+        .collect();
+    unlocked.sort_by_key(|ring| ring.to_string());
+    for ring in unlocked {
+        items.push(Box::new(ring.clone()));
+    }
+
     items
 }
 
@@ -192,6 +209,7 @@ impl Shoppable for Ring {
     }
 
     fn add_to(&self, game: &mut Game) {
+        game.ring_pool.remove(self);
         game.add_item(Box::new(self.clone()));
     }
 
@@ -265,4 +283,20 @@ mod tests {
         assert_eq!(1, *game.inventory().get(&Key::Potion).unwrap());
         assert!(game.player.shield.is_some());
     }
+
+    #[test]
+    fn buy_unlocked_ring() {
+        let mut game = Game::new();
+        game.gold = 100_000;
+        let ring = Key::Ring(Ring::Gold);
+        assert!(buy(&mut game, std::slice::from_ref(&ring)).is_err());
+
+        game.shop_unlocks.insert(ring.clone());
+        assert!(buy(&mut game, std::slice::from_ref(&ring)).is_ok());
+        assert!(!game.ring_pool.contains(&Ring::Gold));
+        assert_eq!(1, *game.inventory().get(&ring).unwrap());
+
+        // there's only one of each ring
+        assert!(buy(&mut game, std::slice::from_ref(&ring)).is_err());
+    }
 }
@@ -1,6 +1,6 @@
 use super::{key, Item};
+use crate::event::{self, GameEvent};
 use crate::game;
-use crate::log;
 use crate::quest;
 use serde::{Deserialize, Serialize};
 
@@ -101,7 +101,11 @@ impl Item for Level {
 }
 
 fn log(game: &mut game::Game, stat: &'static str, increase: i32) {
-    log::stat_increase(&game.player, stat, increase);
+    event::emit(GameEvent::StatIncrease {
+        player: &game.player,
+        stat,
+        increase,
+    });
 }
 
 // TODO too much duplication
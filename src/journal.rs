@@ -0,0 +1,239 @@
+use crate::character::{Affinity, AttackType, Character};
+use crate::datafile;
+use crate::event::{GameEvent, Sink};
+use crate::item::key::Key;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use strum_macros::Display;
+
+/// A past game event, as recorded in the journal.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Entry {
+    pub time: DateTime<Local>,
+    pub kind: Kind,
+    pub text: String,
+}
+
+/// The journal entry categories that can be used to filter the `log` command.
+#[derive(Serialize, Deserialize, Display, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Kind {
+    Battle,
+    Chest,
+    Quest,
+    Other,
+}
+
+/// Collects a text description of the relevant game events and appends them
+/// to the journal file when the game is saved, so they can be reviewed
+/// after the terminal output is gone.
+#[derive(Default)]
+pub struct Journal {
+    entries: Vec<Entry>,
+}
+
+impl Sink for Journal {
+    fn handle(&mut self, event: &GameEvent) {
+        if let GameEvent::GameSaved { .. } = event {
+            if !self.entries.is_empty() {
+                // losing the journal shouldn't break the game
+                let _ = datafile::append_journal(&self.entries);
+                self.entries.clear();
+            }
+        } else if let Some((kind, text)) = describe(event) {
+            self.entries.push(Entry {
+                time: Local::now(),
+                kind,
+                text,
+            });
+        }
+    }
+}
+
+/// Return the last `count` journal entries, optionally of a single kind.
+pub fn last(count: usize, kind: Option<Kind>) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = datafile::load_journal()
+        .into_iter()
+        .filter(|entry| kind.is_none_or(|kind| entry.kind == kind))
+        .collect();
+    let skip = entries.len().saturating_sub(count);
+    entries.drain(..skip);
+    entries
+}
+
+fn describe(event: &GameEvent) -> Option<(Kind, String)> {
+    let entry = match *event {
+        GameEvent::EnemyAppears { enemy, location } => (
+            Kind::Battle,
+            format!("{} appeared at {}", character(enemy), location),
+        ),
+        GameEvent::Attack {
+            receiver,
+            attack,
+            affinity,
+            damage,
+            mp_cost,
+        } => {
+            let mut text = match attack {
+                AttackType::Regular => hp_change(receiver, -damage),
+                AttackType::Critical => format!("{} critical!", hp_change(receiver, -damage)),
+                AttackType::Effect(status) => {
+                    format!("{} {}!", hp_change(receiver, -damage), status)
+                }
+                AttackType::Miss => format!("{} dodged!", character(receiver)),
+            };
+            match (attack, affinity) {
+                (AttackType::Miss, _) | (_, Affinity::Neutral) => {}
+                (_, Affinity::Weak) => text.push_str(" super effective!"),
+                (_, Affinity::Resistant) => text.push_str(" not very effective"),
+            }
+            if mp_cost > 0 {
+                text.push_str(&format!(" (-{}mp)", mp_cost));
+            }
+            (Kind::Battle, text)
+        }
+        GameEvent::StatusEffect {
+            character: c, hp, ..
+        } if hp != 0 => (Kind::Battle, hp_change(c, hp)),
+        GameEvent::TurnSkipped { character: c } => {
+            (Kind::Battle, format!("{} couldn't move", character(c)))
+        }
+        GameEvent::Skill {
+            character: c,
+            skill,
+            gold,
+        } => {
+            let mut text = format!("{} used {}", character(c), skill.name);
+            if gold > 0 {
+                text.push_str(&format!(" +{}g", gold));
+            }
+            (Kind::Battle, text)
+        }
+        GameEvent::BattleWon {
+            xp,
+            levels_up,
+            gold,
+            items: i,
+            ..
+        } => {
+            let mut text = format!("battle won +{}xp", xp);
+            if levels_up > 0 {
+                text.push_str(&format!(" +{}level", levels_up));
+            }
+            (Kind::Battle, format!("{}{}", text, items(i, gold)))
+        }
+        GameEvent::BattleLost { player, location } => (
+            Kind::Battle,
+            format!("{} died at {}", character(player), location),
+        ),
+        GameEvent::Bribe { amount, .. } if amount > 0 => {
+            (Kind::Battle, format!("bribed the enemy -{}g", amount))
+        }
+        GameEvent::Bribe { .. } => (Kind::Battle, String::from("couldn't bribe the enemy")),
+        GameEvent::RunAway { success: true, .. } => (Kind::Battle, String::from("fled")),
+        GameEvent::RunAway { .. } => (Kind::Battle, String::from("couldn't run away")),
+        GameEvent::ItemHeal {
+            player,
+            item: "revive",
+            hp,
+            ..
+        } => (
+            Kind::Battle,
+            format!("{} revived {}", character(player), hp_change(player, hp)),
+        ),
+        GameEvent::ChestFound {
+            items: i,
+            gold,
+            location,
+        } => (
+            Kind::Chest,
+            format!("chest found at {}{}", location, items(i, gold)),
+        ),
+        GameEvent::TombstoneFound {
+            items: i,
+            gold,
+            location,
+        } => (
+            Kind::Chest,
+            format!("tombstone found at {}{}", location, items(i, gold)),
+        ),
+        GameEvent::QuestDone {
+            description,
+            reward,
+        } => (
+            Kind::Quest,
+            format!("quest completed: {} {}", description, reward),
+        ),
+        GameEvent::ClassChanged { player, .. } => {
+            (Kind::Other, format!("changed class to {}", player.name()))
+        }
+        GameEvent::StatIncrease { stat, increase, .. } => {
+            (Kind::Other, format!("+{}{}", increase, stat))
+        }
+        GameEvent::ShopBuy { cost, items: i } if !i.is_empty() => {
+            (Kind::Other, format!("bought{}", items(i, -cost)))
+        }
+        _ => return None,
+    };
+    Some(entry)
+}
+
+fn character(character: &Character) -> String {
+    format!("{}[{}]", character.name(), character.level)
+}
+
+fn hp_change(receiver: &Character, amount: i32) -> String {
+    format!(
+        "{} {:+}hp {}/{}",
+        character(receiver),
+        amount,
+        receiver.current_hp,
+        receiver.max_hp()
+    )
+}
+
+fn items(items: &HashMap<Key, i32>, gold: i32) -> String {
+    let mut items: Vec<String> = items
+        .iter()
+        .map(|(key, count)| format!(" +{}x{}", key, count))
+        .collect();
+    items.sort();
+    if gold != 0 {
+        items.insert(0, format!(" {:+}g", gold));
+    }
+    items.concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+
+    #[test]
+    fn describe_events() {
+        let game = Game::new();
+        let mut items = HashMap::new();
+        items.insert(Key::Potion, 2);
+
+        let (kind, text) = describe(&GameEvent::ChestFound {
+            items: &items,
+            gold: 10,
+            location: &game.location,
+        })
+        .unwrap();
+        assert_eq!(Kind::Chest, kind);
+        assert_eq!("chest found at home +10g +potionx2", text);
+
+        let (kind, text) = describe(&GameEvent::BattleLost {
+            player: &game.player,
+            location: &game.location,
+        })
+        .unwrap();
+        assert_eq!(Kind::Battle, kind);
+        assert!(text.ends_with("died at home"));
+
+        assert!(describe(&GameEvent::Status { game: &game }).is_none());
+    }
+}
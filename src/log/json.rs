@@ -0,0 +1,284 @@
+use crate::character::{AttackType, Character};
+use crate::event::{GameEvent, Sink};
+use crate::game::Game;
+use crate::item::key::Key;
+use crate::quest::Status;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Accumulates the game events during the command and prints them, along
+/// with the resulting game state and the error if any, as a single JSON
+/// document when the command is done.
+#[derive(Default)]
+pub struct JsonPrinter {
+    events: Vec<Value>,
+    state: Value,
+}
+
+impl Sink for JsonPrinter {
+    fn handle(&mut self, event: &GameEvent) {
+        let mut value = match *event {
+            GameEvent::EnemyAppears { enemy, location } => json!({
+                "enemy": character(enemy),
+                "location": location.to_string(),
+            }),
+            GameEvent::Attack {
+                receiver,
+                attack,
+                affinity,
+                damage,
+                mp_cost,
+            } => {
+                let (attack_type, status) = match attack {
+                    AttackType::Regular => ("regular", None),
+                    AttackType::Critical => ("critical", None),
+                    AttackType::Effect(status) => ("effect", Some(status)),
+                    AttackType::Miss => ("miss", None),
+                };
+                json!({
+                    "receiver": character(receiver),
+                    "type": attack_type,
+                    "status_effect": status,
+                    "affinity": affinity,
+                    "damage": damage,
+                    "mp_cost": mp_cost,
+                })
+            }
+            GameEvent::StatusEffect {
+                character: c,
+                hp,
+                mp,
+            } if hp != 0 || mp != 0 => json!({
+                "character": character(c),
+                "status_effect": c.status_effect,
+                "hp": hp,
+                "mp": mp,
+            }),
+            GameEvent::TurnSkipped { character: c } => json!({
+                "character": character(c),
+                "status_effect": c.status_effect,
+            }),
+            GameEvent::Skill {
+                character: c,
+                skill,
+                gold,
+            } => json!({
+                "character": character(c),
+                "skill": skill.name,
+                "action": skill.action,
+                "gold": gold,
+            }),
+            GameEvent::BattleWon {
+                xp,
+                levels_up,
+                gold,
+                items: i,
+                ..
+            } => json!({
+                "xp": xp,
+                "levels_up": levels_up,
+                "gold": gold,
+                "items": items(i),
+            }),
+            GameEvent::BattleLost { player, location } => json!({
+                "character": character(player),
+                "location": location.to_string(),
+            }),
+            GameEvent::ChestFound {
+                items: i,
+                gold,
+                location,
+            }
+            | GameEvent::TombstoneFound {
+                items: i,
+                gold,
+                location,
+            } => json!({
+                "items": items(i),
+                "gold": gold,
+                "location": location.to_string(),
+            }),
+            GameEvent::Bribe { amount, .. } => json!({"success": amount > 0, "gold": amount}),
+            GameEvent::RunAway { success, .. } => json!({ "success": success }),
+            GameEvent::ItemHeal {
+                item,
+                hp,
+                mp,
+                healed,
+                ..
+            } if hp > 0 || mp > 0 || healed => json!({
+                "item": item,
+                "hp": hp,
+                "mp": mp,
+                "healed": healed,
+            }),
+            GameEvent::Heal {
+                location,
+                hp,
+                mp,
+                healed,
+                ..
+            } if hp > 0 || mp > 0 || healed => json!({
+                "location": location.to_string(),
+                "hp": hp,
+                "mp": mp,
+                "healed": healed,
+            }),
+            GameEvent::ClassChanged { player, lost_xp } => {
+                json!({"class": player.name(), "lost_xp": lost_xp})
+            }
+            GameEvent::StatIncrease { stat, increase, .. } => {
+                json!({"stat": stat, "increase": increase})
+            }
+            GameEvent::ShopList { items: i, .. } => {
+                let i: Vec<Value> = i
+                    .iter()
+                    .map(|(cost, item)| json!({"item": item, "cost": cost}))
+                    .collect();
+                json!({ "items": i })
+            }
+            GameEvent::ShopBuy { cost, items: i } => json!({"items": items(i), "cost": cost}),
+            GameEvent::QuestList { quests } => {
+                let quests: Vec<Value> = quests
+                    .iter()
+                    .map(|quest| {
+                        let (locked, requires) = match &quest.status {
+                            Status::Locked(level) => (Some(*level), None),
+                            Status::Requires(required) => (None, Some(required)),
+                            _ => (None, None),
+                        };
+                        json!({
+                            "description": quest.description,
+                            "completed": quest.status == Status::Completed,
+                            "unlock_level": locked,
+                            "requires": requires,
+                            "reward": quest.reward,
+                            "progress": quest.progress,
+                            "chain": quest.chain,
+                            "period": quest.period,
+                        })
+                    })
+                    .collect();
+                json!({ "quests": quests })
+            }
+            GameEvent::QuestDone {
+                description,
+                reward,
+            } => json!({"description": description, "reward": reward}),
+            GameEvent::Journal { entries } => json!({ "entries": entries }),
+            GameEvent::Stats { stats } => json!({ "stats": stats }),
+            GameEvent::Graveyard { heroes } => json!({ "heroes": heroes }),
+            GameEvent::Bestiary { entries, .. } => {
+                // don't reveal the names of the enemies not met yet
+                let entries: Vec<Value> = entries
+                    .iter()
+                    .map(|entry| match &entry.encounter {
+                        Some(encounter) => json!({"name": entry.name, "encounter": encounter}),
+                        None => json!({"name": "???", "encounter": null}),
+                    })
+                    .collect();
+                json!({ "entries": entries })
+            }
+            GameEvent::ClassOptions { classes } => json!({ "classes": classes }),
+            GameEvent::ProfileList { profiles, current } => json!({
+                "profiles": profiles,
+                "current": current,
+            }),
+            GameEvent::Tactics { tactics } => json!({ "tactics": tactics }),
+            GameEvent::ItemDescription { item, description } => {
+                json!({"item": item, "description": description})
+            }
+            GameEvent::GameSaved { game } => {
+                self.state = state(game);
+                return;
+            }
+            // the rest is either empty or already included in the final state
+            _ => return,
+        };
+        value["event"] = json!(event.name());
+        self.events.push(value);
+    }
+
+    fn finish(&mut self, error: Option<&anyhow::Error>) {
+        let document = json!({
+            "events": self.events,
+            "state": self.state,
+            // an empty error message means the hero died, already reported as an event
+            "error": error.map(|e| e.to_string()).filter(|e| !e.is_empty()),
+        });
+        println!("{}", document);
+    }
+}
+
+fn character(character: &Character) -> Value {
+    json!({
+        "name": character.name(),
+        "level": character.level,
+        "hp": character.current_hp,
+        "max_hp": character.max_hp(),
+    })
+}
+
+fn items(items: &HashMap<Key, i32>) -> Value {
+    items
+        .iter()
+        .map(|(key, count)| (key.to_string(), json!(count)))
+        .collect::<serde_json::Map<String, Value>>()
+        .into()
+}
+
+fn state(game: &Game) -> Value {
+    let player = &game.player;
+    let inventory = game
+        .inventory()
+        .iter()
+        .map(|(key, count)| (key.to_string(), json!(count)))
+        .collect::<serde_json::Map<String, Value>>();
+
+    json!({
+        "player": {
+            "class": player.name(),
+            "level": player.level,
+            "hp": player.current_hp,
+            "max_hp": player.max_hp(),
+            "mp": player.current_mp,
+            "max_mp": player.max_mp(),
+            "xp": player.xp,
+            "xp_for_next": player.xp_for_next(),
+            "status_effect": player.status_effect.map(|s| json!(s)),
+            "attack": player.physical_attack(),
+            "magic": player.magic_attack(),
+            "defense": player.deffense(),
+            "speed": player.speed(),
+            "equipment": {
+                "sword": player.sword.as_ref().map(|s| s.to_string()),
+                "shield": player.shield.as_ref().map(|s| s.to_string()),
+                "left_ring": player.left_ring.as_ref().map(|r| r.to_string()),
+                "right_ring": player.right_ring.as_ref().map(|r| r.to_string()),
+            },
+        },
+        "location": game.location.to_string(),
+        "path": game.location.path_string(),
+        "gold": game.gold,
+        "inventory": inventory,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_state() {
+        let mut game = Game::new();
+        game.gold = 50;
+        game.add_item(Box::new(crate::item::Potion::new(1)));
+
+        let state = state(&game);
+        assert_eq!(50, state["gold"]);
+        assert_eq!(1, state["player"]["level"]);
+        assert_eq!(game.player.max_hp(), state["player"]["max_hp"]);
+        assert_eq!(1, state["inventory"]["potion"]);
+        assert!(state["player"]["equipment"]["sword"].is_null());
+    }
+}
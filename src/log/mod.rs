@@ -0,0 +1,910 @@
+use crate::bestiary;
+use crate::character::class;
+use crate::character::AttackType;
+use crate::character::{Action, Affinity, Character, Skill, StatusEffect};
+use crate::config::Tactics;
+use crate::event::{self, GameEvent, Sink};
+use crate::game::Game;
+use crate::graveyard::Fallen;
+use crate::item::key::Key;
+use crate::journal;
+use crate::location::Location;
+use crate::quest;
+use crate::stats::Stats;
+use colored::*;
+use std::collections::HashMap;
+
+mod json;
+
+/// Register the output sinks according to the given preferences.
+/// Regardless of those, the events are also recorded in the journal.
+pub fn init(quiet: bool, plain: bool, json: bool) {
+    event::register(Box::new(journal::Journal::default()));
+    if json {
+        event::register(Box::new(json::JsonPrinter::default()));
+    } else {
+        event::register(Box::new(Printer { quiet, plain }));
+    }
+}
+
+/// Prints the game events to stdout as text, with colors and emojis unless
+/// plain output is requested, and skipping battle details on quiet mode.
+struct Printer {
+    quiet: bool,
+    plain: bool,
+}
+
+impl Sink for Printer {
+    fn handle(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::EnemyAppears { enemy, location } => log(enemy, location, ""),
+            GameEvent::Attack {
+                receiver,
+                attack,
+                affinity,
+                damage,
+                mp_cost,
+            } => self.attack(receiver, attack, affinity, damage, mp_cost),
+            GameEvent::StatusEffect { character, hp, mp } => status_effect(character, hp, mp),
+            GameEvent::TurnSkipped { character } => turn_skipped(character),
+            GameEvent::Skill {
+                character,
+                skill,
+                gold,
+            } => self.skill(character, skill, gold),
+            GameEvent::BattleWon {
+                game,
+                xp,
+                levels_up,
+                gold,
+                items,
+            } => battle_won(game, xp, levels_up, gold, items),
+            GameEvent::BattleLost { player, .. } => battle_log(player, "\u{1F480}"),
+            GameEvent::ChestFound { items, gold, .. } => {
+                println!("{}", format_ls("\u{1F4E6}", items, gold))
+            }
+            GameEvent::TombstoneFound { items, gold, .. } => {
+                println!("{}", format_ls("\u{1FAA6} ", items, gold))
+            }
+            GameEvent::Bribe { player, amount } => bribe(player, amount),
+            GameEvent::RunAway { player, success } => run_away(player, success),
+            GameEvent::ItemHeal {
+                player,
+                item,
+                hp,
+                mp,
+                healed,
+            } => heal_item(player, item, hp, mp, healed),
+            GameEvent::Heal {
+                player,
+                location,
+                hp,
+                mp,
+                healed,
+            } => heal(player, location, hp, mp, healed),
+            GameEvent::ClassChanged { player, lost_xp } => change_class(player, lost_xp),
+            GameEvent::StatIncrease {
+                player,
+                stat,
+                increase,
+            } => stat_increase(player, stat, increase),
+            GameEvent::ShopList { game, items } => shop_list(game, items),
+            GameEvent::ShopBuy { cost, items } => shop_buy(cost, items),
+            GameEvent::QuestList { quests } => quest_list(quests),
+            GameEvent::QuestDone { reward, .. } => self.quest_done(reward),
+            GameEvent::Status { game } => self.status(game),
+            GameEvent::Inventory { game } => println!("{}", format_inventory(game)),
+            GameEvent::WorkDir { location } => println!("{}", location.path_string()),
+            GameEvent::ClassOptions { classes } => println!("Options: {}", classes.join(", ")),
+            GameEvent::ProfileList { profiles, current } => self.profile_list(profiles, current),
+            GameEvent::Tactics { tactics } => self.tactics(tactics),
+            GameEvent::ItemDescription { item, description } => {
+                println!("{}: {}", item, description)
+            }
+            GameEvent::Journal { entries } => journal(entries),
+            GameEvent::Stats { stats } => self.stats(stats),
+            GameEvent::Graveyard { heroes } => self.graveyard(heroes),
+            GameEvent::Bestiary { entries, detailed } => self.bestiary(entries, detailed),
+            GameEvent::GameSaved { .. } => {}
+        }
+    }
+
+    fn finish(&mut self, error: Option<&anyhow::Error>) {
+        if let Some(err) = error {
+            // don't print a new line if error message is empty
+            if !err.to_string().is_empty() {
+                println!("{}", err);
+            }
+        }
+    }
+}
+
+impl Printer {
+    fn attack(
+        &self,
+        character: &Character,
+        attack: &AttackType,
+        affinity: Affinity,
+        damage: i32,
+        mp_cost: i32,
+    ) {
+        if !self.quiet {
+            battle_log(
+                character,
+                &format_attack(character, attack, affinity, damage, mp_cost),
+            );
+        }
+    }
+
+    fn skill(&self, character: &Character, skill: &Skill, gold: i32) {
+        if !self.quiet {
+            let suffix = match skill.action {
+                Action::Steal if gold > 0 => format!("{} {}", skill.name, format_gold_signed(gold)),
+                Action::Steal => format!("{} missed!", skill.name),
+                _ => format!("{}!", skill.name),
+            };
+            battle_log(character, &suffix);
+        }
+    }
+
+    /// Print the hero status according to options
+    fn status(&self, game: &Game) {
+        if self.plain {
+            plain_status(game);
+        } else if self.quiet {
+            short_status(game);
+        } else {
+            long_status(game)
+        }
+    }
+
+    fn stats(&self, stats: &Stats) {
+        if self.plain {
+            plain_stats(stats);
+        } else {
+            long_stats(stats);
+        }
+    }
+
+    fn tactics(&self, tactics: &Tactics) {
+        if self.plain {
+            println!(
+                "potion_hp:{}\tether_mp:{}\tremedy:{}\tescape_hp:{}\tattack:{}",
+                tactics.potion_hp,
+                tactics.ether_mp,
+                tactics.remedy,
+                tactics.escape_hp,
+                tactics.attack
+            );
+        } else {
+            println!(
+                "    potion below:{}   ether below:{}",
+                format!("{}%hp", tactics.potion_hp).green(),
+                format!("{}%mp", tactics.ether_mp).purple()
+            );
+            println!(
+                "    escape below:{}",
+                format!("{}%hp", tactics.escape_hp).green()
+            );
+            println!("    remedy:{}   attack:{}", tactics.remedy, tactics.attack);
+        }
+    }
+
+    fn profile_list(&self, profiles: &[String], current: &str) {
+        for name in profiles {
+            if self.plain {
+                let marker = if name == current { "\tcurrent" } else { "" };
+                println!("{}{}", name, marker);
+            } else {
+                let marker = if name == current { "*" } else { " " };
+                println!("{} {}", marker, name);
+            }
+        }
+    }
+
+    fn graveyard(&self, heroes: &[Fallen]) {
+        for hero in heroes {
+            let date = hero.time.format("%Y-%m-%d");
+            if self.plain {
+                println!(
+                    "{}[{}]\tg:{}\tkiller:{}\t@{}\t{}",
+                    hero.class, hero.level, hero.gold, hero.killer, hero.location, date
+                );
+            } else {
+                println!(
+                    "{:>8}[{}] {} \u{1F480} {} @{} {}",
+                    hero.class.bold(),
+                    hero.level,
+                    format_gold(hero.gold),
+                    hero.killer.bright_red(),
+                    hero.location,
+                    date.to_string().dimmed()
+                );
+            }
+        }
+    }
+
+    fn bestiary(&self, entries: &[bestiary::Entry], detailed: bool) {
+        for entry in entries {
+            match (&entry.encounter, detailed) {
+                (None, _) => println!("{:>8}", "???".dimmed()),
+                (Some(encounter), false) if self.plain => println!(
+                    "{}\t{}\tseen:{}\tbeaten:{}",
+                    entry.name,
+                    format_category(&encounter.class.category),
+                    encounter.seen,
+                    encounter.beaten
+                ),
+                (Some(encounter), false) => println!(
+                    "{:>8} {:<9} seen:{} beaten:{}",
+                    entry.name.yellow().bold(),
+                    format_category(&encounter.class.category),
+                    encounter.seen,
+                    encounter.beaten
+                ),
+                (Some(encounter), true) => self.bestiary_entry(&entry.name, encounter),
+            }
+        }
+    }
+
+    fn bestiary_entry(&self, name: &str, encounter: &bestiary::Encounter) {
+        let class = &encounter.class;
+        let stat = |stat: &class::Stat| format!("{}+{}", stat.0, stat.1);
+        let mp = class.mp.as_ref().map_or(String::from("-"), stat);
+        let inflicts = class.inflicts.map_or(String::from("-"), |(status, ratio)| {
+            format!("{} 1/{}", status, ratio)
+        });
+        let distance = format!("{}-{}", encounter.nearest, encounter.farthest);
+
+        if self.plain {
+            println!(
+                "{}\t{}\thp:{}\tmp:{}\tstr:{}\tspd:{}\tinflicts:{}\tseen:{}\tbeaten:{}\tdistance:{}",
+                name,
+                format_category(&class.category),
+                stat(&class.hp),
+                mp,
+                stat(&class.strength),
+                stat(&class.speed),
+                inflicts,
+                encounter.seen,
+                encounter.beaten,
+                distance
+            );
+        } else {
+            println!(
+                "{} {}",
+                name.yellow().bold(),
+                format_category(&class.category)
+            );
+            println!(
+                "    hp:{}   mp:{}   str:{}   spd:{}",
+                stat(&class.hp),
+                mp,
+                stat(&class.strength),
+                stat(&class.speed)
+            );
+            println!("    inflicts:{}", inflicts);
+            println!(
+                "    seen:{}   beaten:{}   distance:{}",
+                encounter.seen, encounter.beaten, distance
+            );
+        }
+    }
+
+    fn quest_done(&self, reward: &quest::Reward) {
+        if !self.quiet {
+            println!("   {} quest completed!", reward.to_string().yellow());
+        }
+    }
+}
+
+fn status_effect(character: &Character, hp: i32, mp: i32) {
+    if hp != 0 || mp != 0 {
+        let emoji = character
+            .status_effect
+            .map_or("", |s| status_effect_params(s).1);
+
+        battle_log(
+            character,
+            &format_stat_change(character, hp, mp, false, emoji),
+        );
+    }
+}
+
+fn turn_skipped(character: &Character) {
+    if let Some(status) = character.status_effect {
+        let (_, emoji) = status_effect_params(status);
+        battle_log(character, &format!("{} can't move!", emoji));
+    }
+}
+
+fn battle_won(game: &Game, xp: i32, levels_up: i32, gold: i32, items: &HashMap<Key, i32>) {
+    battle_log(
+        &game.player,
+        &format!(
+            "{}{}{}",
+            format!("+{}xp", xp).bold(),
+            level_up(levels_up),
+            format_ls("", items, gold)
+        ),
+    );
+    short_status(game);
+}
+
+fn bribe(player: &Character, amount: i32) {
+    if amount > 0 {
+        let suffix = format!("bribed {}", format_gold_signed(-amount));
+        battle_log(player, &suffix);
+    } else {
+        battle_log(player, "can't bribe!");
+    }
+}
+
+fn run_away(player: &Character, success: bool) {
+    if success {
+        battle_log(player, "fled!");
+    } else {
+        battle_log(player, "can't run!");
+    }
+}
+
+fn heal_item(player: &Character, item: &str, recovered_hp: i32, recovered_mp: i32, healed: bool) {
+    let color = if recovered_mp > 0 { "purple" } else { "green" };
+
+    if recovered_hp > 0 || recovered_mp > 0 || healed {
+        battle_log(
+            player,
+            &format_stat_change(
+                player,
+                recovered_hp,
+                recovered_mp,
+                healed,
+                &item.color(color).to_string(),
+            ),
+        );
+    }
+}
+
+fn heal(
+    player: &Character,
+    location: &Location,
+    recovered_hp: i32,
+    recovered_mp: i32,
+    healed: bool,
+) {
+    if recovered_hp > 0 || recovered_mp > 0 || healed {
+        log(
+            player,
+            location,
+            &format_stat_change(player, recovered_hp, recovered_mp, healed, ""),
+        );
+    }
+}
+
+fn change_class(player: &Character, lost_xp: i32) {
+    if lost_xp > 0 {
+        battle_log(player, &format!("-{}xp", lost_xp).bright_red().to_string());
+    }
+}
+
+fn stat_increase(player: &Character, stat: &str, increase: i32) {
+    let suffix = if stat == "level" {
+        level_up(increase)
+    } else {
+        format!("+{}{}", increase, stat).cyan().to_string()
+    };
+    battle_log(player, &suffix);
+}
+
+fn shop_list(game: &Game, items: &[(i32, String)]) {
+    for (cost, item) in items {
+        println!("    {:<10}  {}", item, format_gold(*cost));
+    }
+
+    println!("\n    funds: {}", format_gold(game.gold));
+}
+
+fn shop_buy(cost: i32, items: &HashMap<Key, i32>) {
+    if !items.is_empty() {
+        println!("  {}", format_ls("", items, -cost));
+    }
+}
+
+fn quest_list(quests: &[quest::Entry]) {
+    let mut current_chain = None;
+    for quest in quests {
+        // steps of the same chain are listed together under its storyline
+        if let Some(chain) = quest.chain.as_ref().filter(|c| Some(*c) != current_chain) {
+            println!("  {} {}", chain.name.bold(), chain.story.dimmed());
+        }
+        current_chain = quest.chain.as_ref();
+        let indent = if current_chain.is_some() {
+            "    "
+        } else {
+            "  "
+        };
+
+        let progress = match quest.progress {
+            Some((current, total)) if quest.status != quest::Status::Completed => {
+                format!(" {}/{}", current, total)
+            }
+            _ => String::new(),
+        };
+        let description = match quest.period {
+            Some(period) => format!("{}: {}{}", period, quest.description, progress),
+            None => format!("{}{}", quest.description, progress),
+        };
+
+        match &quest.status {
+            quest::Status::Completed => {
+                println!("{}{} {}", indent, "✔".green(), description.dimmed())
+            }
+            quest::Status::Unlocked => println!(
+                "{}{} {} {}",
+                indent,
+                "□".dimmed(),
+                description,
+                quest.reward.to_string().dimmed()
+            ),
+            quest::Status::Locked(level) => println!(
+                "{}{} {} {}",
+                indent,
+                "\u{1F512}".dimmed(),
+                description.dimmed(),
+                format!("lvl:{} {}", level, quest.reward).dimmed()
+            ),
+            quest::Status::Requires(required) => println!(
+                "{}{} {} {}",
+                indent,
+                "\u{1F512}".dimmed(),
+                description.dimmed(),
+                format!("after:{} {}", required, quest.reward).dimmed()
+            ),
+        }
+    }
+}
+
+fn journal(entries: &[journal::Entry]) {
+    for entry in entries {
+        let kind = format!("{:<6}", entry.kind.to_string());
+        let kind = match entry.kind {
+            journal::Kind::Battle => kind.red(),
+            journal::Kind::Chest => kind.yellow(),
+            journal::Kind::Quest => kind.green(),
+            journal::Kind::Other => kind.dimmed(),
+        };
+        println!(
+            "{} {} {}",
+            entry.time.format("%Y-%m-%d %H:%M").to_string().dimmed(),
+            kind,
+            entry.text
+        );
+    }
+}
+
+fn level_up(levels_up: i32) -> String {
+    if levels_up > 0 {
+        let plus = (0..levels_up).map(|_| "+").collect::<String>();
+        format!(" {}level", plus).cyan().to_string()
+    } else {
+        "".to_string()
+    }
+}
+
+fn long_status(game: &Game) {
+    let player = &game.player;
+    let location = &game.location;
+
+    println!("{}@{}", format_character(player), location);
+    println!(
+        "    hp:{} {}/{}",
+        hp_display(player, 10),
+        player.current_hp,
+        player.max_hp()
+    );
+
+    let (current_mp, max_mp) = if player.class.is_magic() {
+        (player.current_mp, player.max_mp())
+    } else {
+        (0, 0)
+    };
+    println!(
+        "    mp:{} {}/{}",
+        mp_display(player, 10),
+        current_mp,
+        max_mp
+    );
+
+    println!(
+        "    xp:{} {}/{}",
+        xp_display(player, 10),
+        player.xp,
+        player.xp_for_next()
+    );
+    if let Some(status) = player.status_effect {
+        println!("    status: {}", format_status_effect(status).bright_red());
+    }
+    println!(
+        "    att:{}   mag:{}   def:{}   spd:{}",
+        player.physical_attack(),
+        player.magic_attack(),
+        player.deffense(),
+        player.speed()
+    );
+    println!("    {}", format_equipment(player));
+    println!("    {}", format_inventory(game));
+    println!("    {}", format_gold(game.gold));
+}
+
+fn short_status(game: &Game) {
+    let player = &game.player;
+
+    let suffix = if let Some(status) = player.status_effect {
+        let (_, emoji) = status_effect_params(status);
+        emoji
+    } else {
+        ""
+    };
+    log(player, &game.location, suffix);
+}
+
+fn plain_status(game: &Game) {
+    let player = &game.player;
+
+    let status_effect = if let Some(status) = player.status_effect {
+        let (name, _) = status_effect_params(status);
+        format!("status:{}\t", name)
+    } else {
+        String::new()
+    };
+
+    println!(
+        "{}[{}]\t@{}\thp:{}/{}\tmp:{}/{}\txp:{}/{}\tatt:{}\tmag:{}\tdef:{}\tspd:{}\t{}{}\t{}\tg:{}",
+        player.name(),
+        player.level,
+        game.location,
+        player.current_hp,
+        player.max_hp(),
+        player.current_mp,
+        player.max_mp(),
+        player.xp,
+        player.xp_for_next(),
+        player.magic_attack(),
+        player.physical_attack(),
+        player.deffense(),
+        player.speed(),
+        status_effect,
+        format_equipment(player),
+        format_inventory(game),
+        game.gold
+    );
+}
+
+fn long_stats(stats: &Stats) {
+    println!(
+        "    battles:{}   won:{}   deaths:{}",
+        stats.battles, stats.victories, stats.deaths
+    );
+    println!("    deepest:{}", stats.deepest);
+    println!("    earned:{}", format_gold(stats.gold_earned));
+    println!(
+        "    beaten:{}",
+        format_counts(&stats.enemies_beaten).yellow()
+    );
+    println!(
+        "    killed by:{}",
+        format_counts(&stats.death_causes).bright_red()
+    );
+}
+
+fn plain_stats(stats: &Stats) {
+    println!(
+        "battles:{}\twon:{}\tdeaths:{}\tdeepest:{}\tearned:{}\tbeaten:{{{}}}\tkilled_by:{{{}}}",
+        stats.battles,
+        stats.victories,
+        stats.deaths,
+        stats.deepest,
+        stats.gold_earned,
+        format_counts(&stats.enemies_beaten)
+            .trim()
+            .replace(' ', ","),
+        format_counts(&stats.death_causes).trim().replace(' ', ",")
+    );
+}
+
+/// Format the counts sorted from the most frequent, e.g. " slimex3 wolfx1".
+fn format_counts(counts: &HashMap<String, i32>) -> String {
+    let mut counts: Vec<_> = counts.iter().collect();
+    counts.sort_by(|(a_name, a), (b_name, b)| b.cmp(a).then(a_name.cmp(b_name)));
+    counts
+        .iter()
+        .map(|(name, count)| format!(" {}x{}", name, count))
+        .collect()
+}
+
+fn format_category(category: &class::Category) -> &'static str {
+    match category {
+        class::Category::Player => "player",
+        class::Category::Common => "common",
+        class::Category::Rare => "rare",
+        class::Category::Legendary => "legendary",
+    }
+}
+
+fn format_ls(emoji: &str, items: &HashMap<Key, i32>, gold: i32) -> String {
+    let mut string = format!("{} ", emoji);
+
+    if gold != 0 {
+        string.push_str(&format!("{} ", format_gold_signed(gold)));
+    }
+    for (key, count) in items {
+        string.push_str(&format!("+{}x{} ", key, count));
+    }
+    string
+}
+
+// HELPERS
+
+/// Generic log function. At the moment all output of the game is structured as
+/// of a player status at some location, with an optional event suffix.
+fn log(character: &Character, location: &Location, suffix: &str) {
+    println!(
+        "{}{}{}{}@{} {}",
+        format_character(character),
+        hp_display(character, 4),
+        mp_display(character, 4),
+        xp_display(character, 4),
+        location,
+        suffix
+    );
+}
+
+fn battle_log(character: &Character, suffix: &str) {
+    println!(
+        "{}{} {}",
+        format_character(character),
+        hp_display(character, 4),
+        suffix
+    );
+}
+
+fn format_character(character: &Character) -> String {
+    let name = format!("{:>8}", character.name());
+    let name = if character.name() == "shadow" {
+        name.dimmed()
+    } else if character.is_player() {
+        name.bold()
+    } else {
+        name.yellow().bold()
+    };
+    format!("{}[{}]", name, character.level)
+}
+
+fn format_equipment(character: &Character) -> String {
+    let mut fragments = Vec::new();
+
+    if let Some(sword) = &character.sword {
+        fragments.push(sword.to_string());
+    }
+
+    if let Some(shield) = &character.shield {
+        fragments.push(shield.to_string());
+    }
+
+    if let Some(ring) = &character.left_ring {
+        fragments.push(ring.to_string());
+    }
+
+    if let Some(ring) = &character.right_ring {
+        fragments.push(ring.to_string());
+    }
+
+    format!("equip:{{{}}}", fragments.join(","))
+}
+
+fn format_inventory(game: &Game) -> String {
+    let mut items = game
+        .inventory()
+        .iter()
+        .map(|(k, v)| format!("{}x{}", k, v))
+        .collect::<Vec<String>>();
+
+    items.sort();
+    format!("item:{{{}}}", items.join(","))
+}
+
+fn format_attack(
+    receiver: &Character,
+    attack: &AttackType,
+    affinity: Affinity,
+    damage: i32,
+    mp_cost: i32,
+) -> String {
+    let magic_effect = if mp_cost > 0 {
+        format!("\u{2728} -{}mp ", mp_cost).purple().to_string()
+    } else {
+        String::from("")
+    };
+    let effectiveness = match affinity {
+        Affinity::Weak => "It's super effective! ",
+        Affinity::Resistant => "It's not very effective... ",
+        Affinity::Neutral => "",
+    };
+
+    match attack {
+        AttackType::Regular => format_hp_change(
+            receiver,
+            -damage,
+            &format!("{}{}", magic_effect, effectiveness),
+        ),
+        AttackType::Critical => format_hp_change(
+            receiver,
+            -damage,
+            &format!("{}critical! {}", magic_effect, effectiveness),
+        ),
+        AttackType::Effect(status_effect) => format_hp_change(
+            receiver,
+            -damage,
+            &format!("{} {}", format_status_effect(*status_effect), effectiveness),
+        ),
+        AttackType::Miss => format!("{}dodged!", magic_effect),
+    }
+}
+
+fn format_stat_change(
+    receiver: &Character,
+    hp: i32,
+    mp: i32,
+    healed: bool,
+    suffix: &str,
+) -> String {
+    let mut healed_text = String::new();
+    let mut mp_text = String::new();
+
+    if mp != 0 {
+        mp_text = format!("{:+}mp ", mp);
+    }
+    if healed {
+        healed_text = String::from("+healed ");
+    }
+
+    format!(
+        "{}{}{}{}",
+        &format_hp_change(receiver, hp, ""),
+        mp_text.purple(),
+        healed_text.green(),
+        suffix
+    )
+}
+
+fn format_hp_change(receiver: &Character, amount: i32, suffix: &str) -> String {
+    if amount != 0 {
+        let color = if receiver.is_player() {
+            if amount < 0 {
+                "bright red"
+            } else {
+                "green"
+            }
+        } else {
+            "white"
+        };
+        format!("{:+}hp {}", amount, suffix)
+            .color(color)
+            .to_string()
+    } else {
+        String::from("")
+    }
+}
+
+fn format_status_effect(status_effect: StatusEffect) -> String {
+    let (name, emoji) = status_effect_params(status_effect);
+    format!("{} {}!", emoji, name)
+}
+
+fn status_effect_params(status_effect: StatusEffect) -> (String, &'static str) {
+    (status_effect.to_string(), &status_effect.effect().emoji)
+}
+
+fn hp_display(character: &Character, slots: i32) -> String {
+    bar_display(
+        slots,
+        character.current_hp,
+        character.max_hp(),
+        "green",
+        "red",
+    )
+}
+
+fn mp_display(character: &Character, slots: i32) -> String {
+    let current_mp = if character.class.is_magic() {
+        character.current_mp
+    } else {
+        0
+    };
+
+    bar_display(
+        slots,
+        current_mp,
+        character.max_mp(),
+        "purple",
+        "bright black",
+    )
+}
+
+fn xp_display(character: &Character, slots: i32) -> String {
+    if character.is_player() {
+        bar_display(
+            slots,
+            character.xp,
+            character.xp_for_next(),
+            "cyan",
+            "bright black",
+        )
+    } else {
+        // enemies don't have experience
+        String::new()
+    }
+}
+
+fn bar_display(
+    slots: i32,
+    current: i32,
+    total: i32,
+    current_color: &str,
+    missing_color: &str,
+) -> String {
+    let (filled, rest) = bar_slots(slots, total, current);
+    let current = (0..filled)
+        .map(|_| "x")
+        .collect::<String>()
+        .color(current_color);
+    let missing = (0..rest)
+        .map(|_| "-")
+        .collect::<String>()
+        .color(missing_color);
+    format!("[{}{}]", current, missing)
+}
+
+fn bar_slots(slots: i32, total: i32, current: i32) -> (i32, i32) {
+    let units = (current as f64 * slots as f64 / total as f64).ceil() as i32;
+    (units, slots - units)
+}
+
+fn format_gold(gold: i32) -> ColoredString {
+    format!("{}g", gold).yellow()
+}
+
+fn format_gold_signed(gold: i32) -> ColoredString {
+    format!("{:+}g", gold).yellow()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bar_slots() {
+        // simple case 1:1 between points and slots
+        let slots = 4;
+        let total = 4;
+        assert_eq!((0, 4), bar_slots(slots, total, 0));
+        assert_eq!((1, 3), bar_slots(slots, total, 1));
+        assert_eq!((2, 2), bar_slots(slots, total, 2));
+        assert_eq!((3, 1), bar_slots(slots, total, 3));
+        assert_eq!((4, 0), bar_slots(slots, total, 4));
+
+        let total = 10;
+        assert_eq!((0, 4), bar_slots(slots, total, 0));
+        assert_eq!((1, 3), bar_slots(slots, total, 1));
+        assert_eq!((1, 3), bar_slots(slots, total, 2));
+        assert_eq!((2, 2), bar_slots(slots, total, 3));
+        assert_eq!((2, 2), bar_slots(slots, total, 4));
+        assert_eq!((2, 2), bar_slots(slots, total, 5));
+        assert_eq!((3, 1), bar_slots(slots, total, 6));
+        assert_eq!((3, 1), bar_slots(slots, total, 7));
+        // this one I would maybe like to show as 3, 1
+        assert_eq!((4, 0), bar_slots(slots, total, 8));
+        assert_eq!((4, 0), bar_slots(slots, total, 9));
+        assert_eq!((4, 0), bar_slots(slots, total, 10));
+    }
+}
@@ -1,17 +1,24 @@
 use game::Game;
 
+mod bestiary;
 mod character;
 mod command;
+mod config;
 mod datafile;
+mod event;
 mod game;
+mod graveyard;
+mod input;
 mod item;
+mod journal;
 mod location;
 mod log;
 mod quest;
 mod randomizer;
+mod stats;
 
-use anyhow::Result;
-use clap::{crate_version, AppSettings, Clap};
+use anyhow::{anyhow, Result};
+use clap::{crate_version, AppSettings, ArgEnum, Clap};
 
 /// Your filesystem as a dungeon!
 #[derive(Clap)]
@@ -28,15 +35,45 @@ struct Opts {
     /// Print machine-readable output when possible.
     #[clap(long, global = true)]
     plain: bool,
+
+    /// Output format. With json, a single document with the command events,
+    /// the resulting game state and the error, if any, is printed.
+    #[clap(long, arg_enum, global = true, default_value = "text")]
+    format: OutputFormat,
+
+    /// Directory where the game files are stored.
+    /// Defaults to $XDG_DATA_HOME/rpg, with the classes and config files at $XDG_CONFIG_HOME/rpg.
+    #[clap(long, global = true, env = "RPG_HOME", parse(from_os_str))]
+    data_dir: Option<std::path::PathBuf>,
+
+    /// Name of the hero profile to play with, previously created with `profile new`.
+    #[clap(long, global = true, env = "RPG_PROFILE")]
+    profile: Option<String>,
+
+    /// Seconds to wait for other running commands to release the game data.
+    #[clap(long, global = true, env = "RPG_LOCK_TIMEOUT", default_value = "5")]
+    lock_timeout: u64,
+
+    /// Prompt for the hero actions on each battle turn instead of choosing
+    /// them automatically. Ignored when the input is not a terminal.
+    #[clap(long, global = true)]
+    interactive: bool,
+
+    /// Seed the random number generator to get reproducible outcomes.
+    #[clap(long, global = true, env = "RPG_SEED")]
+    seed: Option<u64>,
 }
 
-fn main() {
-    if let Err(err) = run_game() {
-        // don't print a new line if error message is empty
-        if !err.to_string().is_empty() {
-            println!("{}", err);
-        };
+#[derive(ArgEnum, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Text,
+    Json,
+}
 
+fn main() {
+    let result = run_game();
+    event::finish(result.as_ref().err());
+    if result.is_err() {
         std::process::exit(1);
     }
 }
@@ -45,24 +82,52 @@ fn main() {
 /// Inner errors are bubbled up.
 fn run_game() -> Result<()> {
     let opts: Opts = Opts::parse();
-    log::init(opts.quiet, opts.plain);
+    log::init(opts.quiet, opts.plain, opts.format == OutputFormat::Json);
+    randomizer::init(opts.seed);
+    datafile::init(opts.data_dir, opts.profile)?;
     datafile::load_classes();
+    datafile::load_effects()?;
+    datafile::load_quests()?;
+    datafile::load_config()?;
+
+    // don't wait for input that will never come, e.g. when called from the shell hooks
+    let interactive = opts.interactive || config::get().interactive;
+    if interactive && opts.format == OutputFormat::Text && atty::is(atty::Stream::Stdin) {
+        input::set(Box::new(input::Terminal));
+    }
+
+    // profiles are managed regardless of the selected one, which may not exist yet
+    if let Some(command::Command::Profile { action }) = &opts.cmd {
+        return command::profile(action);
+    }
+
+    // hold the lock until the game is saved, to prevent another command
+    // from loading the game in the meantime
+    let _lock = datafile::lock(std::time::Duration::from_secs(opts.lock_timeout))?;
+
+    // restore works directly on the data files, without loading a game
+    if let Some(command::Command::Restore { backup }) = &opts.cmd {
+        return datafile::restore(*backup);
+    }
 
     // reset --hard is a special case, it needs to work when we
     // fail to deserialize the game data -- e.g. on backward
     // incompatible changes
     if let Some(command::Command::Reset { hard: true }) = opts.cmd {
-        datafile::remove();
+        datafile::remove()?;
     }
 
     let mut game = datafile::load()?.unwrap_or_else(Game::new);
+    game.quests.refresh(game.player.level);
+    game.quests.rotate(quest::today());
 
     let result = command::run(opts.cmd, &mut game);
 
     // save the file regardless of the success of the command.
     // E.g. if the player dies it's an error / exit code 1
     // and that needs to be reflected in the game state.
-    datafile::save(&game).unwrap();
+    datafile::save(&game).map_err(|err| anyhow!("Couldn't save the game data: {}.", err))?;
+    event::emit(event::GameEvent::GameSaved { game: &game });
 
     result
 }
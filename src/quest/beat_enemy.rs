@@ -43,6 +43,7 @@ pub fn at_distance(distance: i32) -> Box<dyn Quest> {
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BeatEnemyClass {
+    #[serde(serialize_with = "crate::datafile::sorted")]
     pub to_beat: HashSet<String>,
     pub total: usize,
     pub description: String,
@@ -51,12 +52,7 @@ pub struct BeatEnemyClass {
 #[typetag::serde]
 impl Quest for BeatEnemyClass {
     fn description(&self) -> String {
-        if self.total == 1 {
-            self.description.to_string()
-        } else {
-            let already_beat = self.total - self.to_beat.len();
-            format!("{} {}/{}", self.description, already_beat, self.total)
-        }
+        self.description.to_string()
     }
 
     fn handle(&mut self, event: &Event) -> bool {
@@ -65,6 +61,15 @@ impl Quest for BeatEnemyClass {
         }
         self.to_beat.is_empty()
     }
+
+    fn progress(&self) -> Option<(i32, i32)> {
+        if self.total == 1 {
+            None
+        } else {
+            let already_beat = self.total - self.to_beat.len();
+            Some((already_beat as i32, self.total as i32))
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
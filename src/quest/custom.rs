@@ -0,0 +1,605 @@
+use super::{Chain, Event, Quest, Reward, Status};
+use crate::character::class::Category;
+use crate::character::StatusEffect;
+use crate::item::key::Key;
+use crate::item::Item;
+use crate::location::Location;
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+
+/// The kind of game event that advances a custom quest.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Trigger {
+    BattleWon,
+    LevelUp,
+    ItemBought,
+    ItemUsed,
+    ItemAdded,
+    ChestFound,
+    TombstoneFound,
+    Bribe,
+    RunAway,
+    Visit,
+    Death,
+    ClassChange,
+    StatusEffect,
+    RingEquip,
+    RingUnequip,
+}
+
+/// A quest as declared in the quests file.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Definition {
+    /// Stable name for other quests to refer to, required for chain steps.
+    id: Option<String>,
+
+    description: String,
+    event: Trigger,
+
+    /// Only count battles against enemies of this category.
+    category: Option<Category>,
+
+    /// Only count battles against this enemy class, deaths caused by it,
+    /// or level ups and class changes of this player class.
+    class: Option<String>,
+
+    /// Only count battles, visits and deaths at least this many steps
+    /// away from home.
+    distance: Option<i32>,
+
+    /// Only count events of this item or ring.
+    item: Option<Key>,
+
+    /// Only count the bribes and run away attempts with this outcome.
+    success: Option<bool>,
+
+    /// Only count this status effect.
+    status: Option<StatusEffect>,
+
+    /// How many matching events are required to finish the quest.
+    /// For level ups, the amount of levels raised.
+    #[serde(default = "one")]
+    count: i32,
+
+    /// The quest is locked until the player reaches this level.
+    #[serde(default = "one")]
+    unlock_level: i32,
+
+    /// The quest is locked until the one with this id is finished.
+    requires: Option<String>,
+
+    reward: RewardDefinition,
+
+    #[serde(skip)]
+    chain: Option<Chain>,
+}
+
+/// A sequence of quests sharing a storyline, as declared in the quests file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ChainDefinition {
+    chain: String,
+    story: String,
+    steps: Vec<Definition>,
+}
+
+/// A reward can be just an amount of gold, or the full reward details.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+enum RewardDefinition {
+    Gold(i32),
+    Full(Reward),
+}
+
+impl RewardDefinition {
+    fn reward(&self) -> Reward {
+        match self {
+            RewardDefinition::Gold(gold) => Reward::gold(*gold),
+            RewardDefinition::Full(reward) => reward.clone(),
+        }
+    }
+}
+
+fn one() -> i32 {
+    1
+}
+
+impl Definition {
+    /// Parse the quest definitions from a yaml byte array, verifying that
+    /// each filter makes sense for the quest event. Each entry of the file
+    /// is either a single quest or a chain of quest steps, which are
+    /// returned in order with each step requiring the previous one.
+    pub fn parse(bytes: &[u8]) -> Result<Vec<Self>> {
+        Self::from_entries(serde_yaml::from_slice(bytes)?)
+    }
+
+    /// Like `parse`, for entries already read from a yaml document.
+    pub fn from_entries(entries: Vec<Value>) -> Result<Vec<Self>> {
+        let mut definitions = Vec::new();
+        for entry in entries {
+            if entry.get("steps").is_some() {
+                let chain: ChainDefinition = serde_yaml::from_value(entry)?;
+                definitions.extend(chain.link()?);
+            } else {
+                let definition: Self = serde_yaml::from_value(entry)?;
+                definition.validate()?;
+                definitions.push(definition);
+            }
+        }
+
+        for (index, definition) in definitions.iter().enumerate() {
+            if let Some(id) = &definition.id {
+                if definitions[..index].iter().any(|def| def.has_id(id)) {
+                    bail!("the '{}' quest id is used more than once", id);
+                }
+            }
+            if let Some(required) = &definition.requires {
+                if !definitions.iter().any(|def| def.has_id(required)) {
+                    bail!(
+                        "the '{}' quest requires an unknown quest '{}'",
+                        definition.description,
+                        required
+                    );
+                }
+            }
+        }
+        Ok(definitions)
+    }
+
+    fn validate(&self) -> Result<()> {
+        use Trigger::*;
+        let event = self.event;
+        let filters = [
+            ("category", self.category.is_some(), event == BattleWon),
+            (
+                "class",
+                self.class.is_some(),
+                matches!(event, BattleWon | LevelUp | Death | ClassChange),
+            ),
+            (
+                "distance",
+                self.distance.is_some(),
+                matches!(event, BattleWon | Visit | Death),
+            ),
+            (
+                "item",
+                self.item.is_some(),
+                matches!(
+                    event,
+                    ItemBought | ItemUsed | ItemAdded | RingEquip | RingUnequip
+                ),
+            ),
+            (
+                "success",
+                self.success.is_some(),
+                matches!(event, Bribe | RunAway),
+            ),
+            ("status", self.status.is_some(), event == StatusEffect),
+        ];
+        for (name, present, allowed) in &filters {
+            if *present && !allowed {
+                bail!(
+                    "the {} filter doesn't apply to the '{}' quest",
+                    name,
+                    self.description
+                );
+            }
+        }
+        if self.count < 1 {
+            bail!("the '{}' quest count must be positive", self.description);
+        }
+        self.reward.reward().validate()?;
+        if self.requires.is_some() && self.unlock_level > 1 {
+            bail!(
+                "the '{}' quest can't require both a level and another quest",
+                self.description
+            );
+        }
+        Ok(())
+    }
+
+    /// The initial status, reward and quest state to add to the quest list.
+    pub fn compile(&self) -> (Status, Reward, Box<dyn Quest>) {
+        (self.status(), self.reward.reward(), Box::new(self.quest()))
+    }
+
+    /// Like `compile`, keeping the progress of a quest previously compiled
+    /// from this definition. Its status is kept once it was unlocked.
+    pub fn recompile(&self, status: &Status, quest: &Custom) -> (Status, Reward, Box<dyn Quest>) {
+        let status = match status {
+            Status::Unlocked | Status::Completed => status.clone(),
+            _ => self.status(),
+        };
+        let updated = Custom {
+            done: quest.done,
+            ..self.quest()
+        };
+        (status, self.reward.reward(), Box::new(updated))
+    }
+
+    /// Whether the given quest was compiled from this definition. Quests
+    /// saved without an id are matched by their description.
+    pub fn matches(&self, quest: &Custom) -> bool {
+        match &quest.id {
+            Some(id) => self.has_id(id),
+            None => self.description == quest.description,
+        }
+    }
+
+    fn has_id(&self, id: &str) -> bool {
+        self.id.as_deref() == Some(id)
+    }
+
+    fn status(&self) -> Status {
+        if let Some(required) = &self.requires {
+            Status::Requires(required.clone())
+        } else if self.unlock_level > 1 {
+            Status::Locked(self.unlock_level)
+        } else {
+            Status::Unlocked
+        }
+    }
+
+    fn quest(&self) -> Custom {
+        Custom {
+            id: self.id.clone(),
+            description: self.description.clone(),
+            event: self.event,
+            category: self.category.clone(),
+            class: self.class.clone(),
+            distance: self.distance,
+            item: self.item.clone(),
+            success: self.success,
+            status: self.status,
+            count: self.count,
+            done: 0,
+            chain: self.chain.clone(),
+        }
+    }
+}
+
+/// A quest defined in the quests file, finished after a number of events
+/// matching its filters.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Custom {
+    #[serde(default)]
+    id: Option<String>,
+
+    description: String,
+    event: Trigger,
+    category: Option<Category>,
+    class: Option<String>,
+    distance: Option<i32>,
+    item: Option<Key>,
+
+    #[serde(default)]
+    success: Option<bool>,
+
+    #[serde(default)]
+    status: Option<StatusEffect>,
+
+    count: i32,
+    done: i32,
+
+    #[serde(default)]
+    chain: Option<Chain>,
+}
+
+impl ChainDefinition {
+    /// The chain steps, each one locked until the previous is finished.
+    /// Only the first step can set its own unlock conditions.
+    fn link(self) -> Result<Vec<Definition>> {
+        let chain = Chain {
+            name: self.chain,
+            story: self.story,
+        };
+        let mut previous: Option<String> = None;
+        let mut steps = Vec::new();
+        for mut step in self.steps {
+            step.validate()?;
+            if step.id.is_none() {
+                bail!(
+                    "the '{}' quest needs an id to be a step of a chain",
+                    step.description
+                );
+            }
+            if let Some(required) = previous {
+                if step.requires.is_some() || step.unlock_level > 1 {
+                    bail!(
+                        "the '{}' quest is unlocked by the previous step of its chain",
+                        step.description
+                    );
+                }
+                step.requires = Some(required);
+            }
+            previous = step.id.clone();
+            step.chain = Some(chain.clone());
+            steps.push(step);
+        }
+        Ok(steps)
+    }
+}
+
+impl Custom {
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// How much the event advances the quest.
+    fn steps(&self, event: &Event) -> i32 {
+        let matches = match event {
+            Event::BattleWon { enemy, location } if self.event == Trigger::BattleWon => {
+                self.category
+                    .as_ref()
+                    .is_none_or(|category| *category == enemy.class.category)
+                    && self.has_class(&enemy.name())
+                    && self.has_distance(location)
+            }
+            Event::LevelUp { count, class, .. } if self.event == Trigger::LevelUp => {
+                if self.has_class(class) {
+                    return *count;
+                }
+                false
+            }
+            Event::ItemBought { item } if self.event == Trigger::ItemBought => self.has_item(item),
+            Event::ItemUsed { item } if self.event == Trigger::ItemUsed => self.has_item(item),
+            Event::ItemAdded { item } if self.event == Trigger::ItemAdded => self.has_item(item),
+            Event::ChestFound => self.event == Trigger::ChestFound,
+            Event::TombtsoneFound => self.event == Trigger::TombstoneFound,
+            Event::Bribe { success } if self.event == Trigger::Bribe => self.has_outcome(*success),
+            Event::RunAway { success } if self.event == Trigger::RunAway => {
+                self.has_outcome(*success)
+            }
+            Event::Visit { location } if self.event == Trigger::Visit => {
+                self.has_distance(location)
+            }
+            Event::Died { killer, location } if self.event == Trigger::Death => {
+                self.has_class(killer) && self.has_distance(location)
+            }
+            Event::ClassChanged { class } if self.event == Trigger::ClassChange => {
+                self.has_class(class)
+            }
+            Event::StatusEffect { effect } if self.event == Trigger::StatusEffect => {
+                self.status.is_none_or(|status| status == *effect)
+            }
+            Event::RingEquipped { ring } if self.event == Trigger::RingEquip => {
+                self.has_item(&ring.key())
+            }
+            Event::RingUnequipped { ring } if self.event == Trigger::RingUnequip => {
+                self.has_item(&ring.key())
+            }
+            _ => false,
+        };
+        matches as i32
+    }
+
+    fn has_class(&self, name: &str) -> bool {
+        self.class.as_ref().is_none_or(|class| class == name)
+    }
+
+    fn has_distance(&self, location: &Location) -> bool {
+        self.distance
+            .is_none_or(|distance| location.distance_from_home().len() >= distance)
+    }
+
+    fn has_outcome(&self, success: bool) -> bool {
+        self.success.is_none_or(|expected| expected == success)
+    }
+
+    fn has_item(&self, item: &Key) -> bool {
+        self.item.as_ref().is_none_or(|key| key == item)
+    }
+}
+
+#[typetag::serde]
+impl Quest for Custom {
+    fn description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn handle(&mut self, event: &Event) -> bool {
+        self.done += self.steps(event);
+        self.done >= self.count
+    }
+
+    fn progress(&self) -> Option<(i32, i32)> {
+        if self.count > 1 {
+            Some((self.done.min(self.count), self.count))
+        } else {
+            None
+        }
+    }
+
+    fn chain(&self) -> Option<&Chain> {
+        self.chain.as_ref()
+    }
+
+    fn as_custom(&self) -> Option<&Custom> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::class::Class;
+    use crate::character::Character;
+    use crate::location::tests::location_from;
+    use crate::location::Location;
+
+    #[test]
+    fn parse_and_handle() {
+        let yaml = b"
+- description: beat two common enemies far from home
+  event: battle_won
+  category: common
+  distance: 3
+  count: 2
+  unlock_level: 5
+  reward: 300
+- description: drink a potion
+  event: item_used
+  item: potion
+  reward:
+    xp: 10
+    items: [potion, potion]
+";
+        let definitions = Definition::parse(yaml).unwrap();
+        assert_eq!(2, definitions.len());
+
+        let (status, reward, mut quest) = definitions[0].compile();
+        assert_eq!(Status::Locked(5), status);
+        assert_eq!(Reward::gold(300), reward);
+        assert_eq!(Some((0, 2)), quest.progress());
+
+        let enemy = Character::new(Class::of(Category::Common)[0].clone(), 1);
+        let far = location_from(&format!("{}/a/b/c", Location::home().path_string()));
+        assert!(!quest.handle(&Event::BattleWon {
+            enemy: &enemy,
+            location: Location::home(),
+        }));
+        assert!(!quest.handle(&Event::BattleWon {
+            enemy: &enemy,
+            location: far.clone(),
+        }));
+        assert_eq!(Some((1, 2)), quest.progress());
+        assert!(quest.handle(&Event::BattleWon {
+            enemy: &enemy,
+            location: far,
+        }));
+
+        let (status, reward, mut quest) = definitions[1].compile();
+        assert_eq!(Status::Unlocked, status);
+        assert_eq!(vec![Key::Potion, Key::Potion], reward.items);
+        assert!(!quest.handle(&Event::ItemUsed { item: Key::Ether }));
+        assert!(quest.handle(&Event::ItemUsed { item: Key::Potion }));
+    }
+
+    #[test]
+    fn outcome_and_status_events() {
+        let yaml = b"
+- description: escape 2 battles
+  event: run_away
+  success: true
+  count: 2
+  reward: 100
+- description: survive poison
+  event: status_effect
+  status: poison
+  reward: 100
+";
+        let definitions = Definition::parse(yaml).unwrap();
+
+        let (_, _, mut quest) = definitions[0].compile();
+        assert!(!quest.handle(&Event::RunAway { success: false }));
+        assert!(!quest.handle(&Event::Bribe { success: true }));
+        assert!(!quest.handle(&Event::RunAway { success: true }));
+        assert!(quest.handle(&Event::RunAway { success: true }));
+
+        let (_, _, mut quest) = definitions[1].compile();
+        assert!(!quest.handle(&Event::StatusEffect {
+            effect: StatusEffect::Burn
+        }));
+        assert!(quest.handle(&Event::StatusEffect {
+            effect: StatusEffect::Poison
+        }));
+    }
+
+    #[test]
+    fn invalid_filter() {
+        let yaml = b"
+- description: find a sword chest
+  event: chest_found
+  item: sword
+  reward: 50
+";
+        assert!(Definition::parse(yaml).is_err());
+        assert!(Definition::parse(b"- description: no event\n  reward: 1").is_err());
+
+        let yaml = b"
+- description: beat the dev
+  event: battle_won
+  class: dev
+  requires: find the dev
+  reward: 50
+";
+        assert!(Definition::parse(yaml).is_err());
+
+        let yaml = b"
+- id: chest
+  description: open a chest
+  event: chest_found
+  reward: 50
+- id: chest
+  description: open another chest
+  event: chest_found
+  reward: 50
+";
+        assert!(Definition::parse(yaml).is_err());
+    }
+
+    #[test]
+    fn parse_chain() {
+        let yaml = b"
+- id: tomb
+  description: visit the tomb of a fallen hero
+  event: tombstone_found
+  reward: 100
+- chain: the shadow
+  story: something is following you home
+  steps:
+    - id: shadow-chest
+      description: open a chest
+      event: chest_found
+      requires: tomb
+      reward: 100
+    - id: shadow
+      description: beat your own shadow
+      event: battle_won
+      class: shadow
+      reward: 1000
+";
+        let definitions = Definition::parse(yaml).unwrap();
+        assert_eq!(3, definitions.len());
+        assert!(definitions[0].compile().2.chain().is_none());
+
+        let (status, _, quest) = definitions[1].compile();
+        assert_eq!(Status::Requires(String::from("tomb")), status);
+        assert_eq!("the shadow", quest.chain().unwrap().name);
+
+        let (status, _, quest) = definitions[2].compile();
+        assert_eq!(Status::Requires(String::from("shadow-chest")), status);
+        assert_eq!(definitions[1].compile().2.chain(), quest.chain());
+
+        // only the first step sets its unlock condition
+        let yaml = b"
+- chain: the shadow
+  story: something is following you home
+  steps:
+    - id: shadow-chest
+      description: open a chest
+      event: chest_found
+      reward: 100
+    - id: shadow
+      description: beat your own shadow
+      event: battle_won
+      unlock_level: 10
+      reward: 1000
+";
+        assert!(Definition::parse(yaml).is_err());
+
+        // steps are linked by id
+        let yaml = b"
+- chain: the shadow
+  story: something is following you home
+  steps:
+    - description: open a chest
+      event: chest_found
+      reward: 100
+";
+        assert!(Definition::parse(yaml).is_err());
+    }
+}
@@ -4,11 +4,15 @@ use serde::{Deserialize, Serialize};
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ReachLevel {
     target: i32,
+
+    /// Highest level reached so far, only used to report progress.
+    #[serde(default)]
+    reached: i32,
 }
 
 impl ReachLevel {
     pub fn new(target: i32) -> Self {
-        Self { target }
+        Self { target, reached: 1 }
     }
 }
 
@@ -20,10 +24,15 @@ impl Quest for ReachLevel {
 
     fn handle(&mut self, event: &Event) -> bool {
         if let Event::LevelUp { current, .. } = event {
+            self.reached = self.reached.max(*current);
             return *current >= self.target;
         }
         false
     }
+
+    fn progress(&self) -> Option<(i32, i32)> {
+        Some((self.reached.min(self.target), self.target))
+    }
 }
 
 const TOTAL_LEVELS: i32 = 5;
@@ -37,13 +46,17 @@ pub struct RaiseClassLevels {
 #[typetag::serde]
 impl Quest for RaiseClassLevels {
     fn description(&self) -> String {
-        let progress = TOTAL_LEVELS - self.remaining;
         format!(
-            "raise {} levels with class {} {}/{}",
-            TOTAL_LEVELS, self.class_name, progress, TOTAL_LEVELS
+            "raise {} levels with class {}",
+            TOTAL_LEVELS, self.class_name
         )
     }
 
+    fn progress(&self) -> Option<(i32, i32)> {
+        let progress = (TOTAL_LEVELS - self.remaining).min(TOTAL_LEVELS);
+        Some((progress, TOTAL_LEVELS))
+    }
+
     fn handle(&mut self, event: &Event) -> bool {
         if let Event::LevelUp { count, class, .. } = event {
             if *class == self.class_name {
@@ -1,17 +1,26 @@
 use crate::character::class;
-use crate::character::Character;
+use crate::character::{Character, StatusEffect};
+use crate::event::{self, GameEvent};
 use crate::game;
 use crate::item::key::Key;
+use crate::item::ring::Ring;
 use crate::location::Location;
-use crate::log;
+use anyhow::{anyhow, Result};
 use core::fmt;
+use once_cell::sync::{Lazy, OnceCell};
 use serde::{Deserialize, Serialize};
 
 mod beat_enemy;
+mod custom;
 mod level;
+mod reward;
 mod ring;
+mod rotation;
 mod tutorial;
 
+pub use reward::Reward;
+pub use rotation::{today, Period};
+
 /// A task that is assigned to the player when certain conditions are met.
 /// New quests should implement this trait and be added to QuestList.setup method.
 #[typetag::serde(tag = "type")]
@@ -22,6 +31,22 @@ pub trait Quest {
     /// Update the quest progress based on the given event and
     /// return whether the quest was finished.
     fn handle(&mut self, event: &Event) -> bool;
+
+    /// The (current, total) steps of the quests that take more than one
+    /// to be finished, e.g. beating a set of enemies.
+    fn progress(&self) -> Option<(i32, i32)> {
+        None
+    }
+
+    /// The storyline this quest is a step of, if any.
+    fn chain(&self) -> Option<&Chain> {
+        None
+    }
+
+    /// The quest state, if it was declared in a quests file.
+    fn as_custom(&self) -> Option<&custom::Custom> {
+        None
+    }
 }
 
 impl fmt::Display for dyn Quest {
@@ -34,14 +59,22 @@ impl fmt::Display for dyn Quest {
 /// Each quest is unlocked at a certain level and has completion reward.
 #[derive(Serialize, Deserialize, Default)]
 pub struct QuestList {
-    quests: Vec<(Status, i32, Box<dyn Quest>)>,
+    quests: Vec<(Status, Reward, Box<dyn Quest>)>,
+
+    /// The daily and weekly repeatable quests currently active.
+    #[serde(default)]
+    rotating: Vec<rotation::Rotating>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-enum Status {
+pub enum Status {
     /// The quest won't be visible until the player reaches a specific level
     Locked(i32),
 
+    /// The quest won't be visible until the quest with the given
+    /// id is finished
+    Requires(String),
+
     /// The quest is visible
     Unlocked,
 
@@ -49,6 +82,31 @@ enum Status {
     Completed,
 }
 
+/// A storyline shared by a sequence of quests, where each step is
+/// unlocked by finishing the previous one.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Chain {
+    pub name: String,
+    pub story: String,
+}
+
+// Quests declared in the quests file, used instead of the default ones
+// when present
+static DEFINITIONS: OnceCell<Vec<custom::Definition>> = OnceCell::new();
+
+// Quest chains included in the default quests
+static CHAINS: Lazy<Vec<custom::Definition>> =
+    Lazy::new(|| custom::Definition::parse(include_bytes!("chains.yaml")).unwrap());
+
+/// Replace the default quests with the ones defined in the given yaml
+/// byte array.
+pub fn load(bytes: &[u8]) -> Result<()> {
+    let definitions =
+        custom::Definition::parse(bytes).map_err(|e| anyhow!("Invalid quests file: {}.", e))?;
+    DEFINITIONS.set(definitions).unwrap();
+    Ok(())
+}
+
 // EVENT TRIGGERING FUNCTIONS
 
 pub fn battle_won(game: &mut game::Game, enemy: &Character, levels_up: i32) {
@@ -100,10 +158,69 @@ pub fn game_reset(game: &mut game::Game) {
     handle(game, Event::GameReset);
 }
 
+pub fn bribe(game: &mut game::Game, success: bool) {
+    handle(game, Event::Bribe { success });
+}
+
+pub fn run_away(game: &mut game::Game, success: bool) {
+    handle(game, Event::RunAway { success });
+}
+
+pub fn visit(game: &mut game::Game) {
+    handle(
+        game,
+        Event::Visit {
+            location: game.location.clone(),
+        },
+    );
+}
+
+pub fn died(game: &mut game::Game, killer: &str) {
+    handle(
+        game,
+        Event::Died {
+            killer,
+            location: game.location.clone(),
+        },
+    );
+}
+
+pub fn class_changed(game: &mut game::Game) {
+    handle(
+        game,
+        Event::ClassChanged {
+            class: game.player.name(),
+        },
+    );
+}
+
+pub fn status_effect(game: &mut game::Game, effect: StatusEffect) {
+    handle(game, Event::StatusEffect { effect });
+}
+
+pub fn ring_equipped(game: &mut game::Game, ring: Ring) {
+    handle(game, Event::RingEquipped { ring });
+}
+
+pub fn ring_unequipped(game: &mut game::Game, ring: Ring) {
+    handle(game, Event::RingUnequipped { ring });
+}
+
 fn handle(game: &mut game::Game, event: Event) {
     // it would be preferable to have quests decoupled from the game struct
     // but that makes event handling much more complicated
-    game.gold += game.quests.handle(&event);
+    for (description, reward) in game.quests.handle(&event) {
+        let granted = reward.grant(game);
+        event::emit(GameEvent::QuestDone {
+            description: &description,
+            reward: &granted,
+        });
+
+        let levels_up = game.player.add_experience(granted.xp);
+        if levels_up > 0 {
+            level_up(game, levels_up);
+        }
+    }
 }
 
 pub enum Event<'a> {
@@ -128,114 +245,246 @@ pub enum Event<'a> {
     ChestFound,
     TombtsoneFound,
     GameReset,
+    Bribe {
+        success: bool,
+    },
+    RunAway {
+        success: bool,
+    },
+    Visit {
+        location: Location,
+    },
+    Died {
+        killer: &'a str,
+        location: Location,
+    },
+    ClassChanged {
+        class: String,
+    },
+    StatusEffect {
+        effect: StatusEffect,
+    },
+    RingEquipped {
+        ring: Ring,
+    },
+    RingUnequipped {
+        ring: Ring,
+    },
 }
 
 impl QuestList {
     pub fn new() -> Self {
-        let mut quests = Self { quests: Vec::new() };
+        let mut quests = Self::default();
 
-        quests.setup();
+        quests.refresh(1);
         quests
     }
 
-    /// Load the quests for a new game
+    /// Make the quest list follow the current quests file, so changes to it
+    /// apply to existing games. Quests already in the list keep their
+    /// progress, new ones are added and the ones no longer defined dropped.
+    /// Without a quests file, the default quests are used.
+    pub fn refresh(&mut self, level: i32) {
+        self.sync(DEFINITIONS.get().map(Vec::as_slice), level);
+    }
+
+    fn sync(&mut self, definitions: Option<&[custom::Definition]>, level: i32) {
+        let (built_in, previous): (Vec<_>, Vec<_>) = std::mem::take(&mut self.quests)
+            .into_iter()
+            .partition(|(_, _, quest)| quest.as_custom().is_none());
+        let definitions = if let Some(definitions) = definitions {
+            definitions
+        } else {
+            // keep the built-in quests as they are, unless a quests file
+            // was used until now
+            self.quests = built_in;
+            if self.quests.is_empty() {
+                self.setup();
+            }
+            &CHAINS
+        };
+
+        for definition in definitions {
+            let existing = previous.iter().find_map(|(status, _, quest)| {
+                quest
+                    .as_custom()
+                    .filter(|custom| definition.matches(custom))
+                    .map(|custom| (status, custom))
+            });
+            self.quests.push(match existing {
+                Some((status, quest)) => definition.recompile(status, quest),
+                None => definition.compile(),
+            });
+        }
+
+        // the conditions of the quests may have been reached already
+        let completed: Vec<String> = self
+            .quests
+            .iter()
+            .filter(|(status, _, _)| *status == Status::Completed)
+            .filter_map(|(_, _, quest)| quest.as_custom()?.id().map(String::from))
+            .collect();
+        self.unlock_followups(&completed);
+        for (status, _, _) in &mut self.quests {
+            if let Status::Locked(required) = status {
+                if *required <= level {
+                    *status = Status::Unlocked;
+                }
+            }
+        }
+    }
+
+    /// Add the default quests of new games.
     fn setup(&mut self) {
-        self.quests
-            .push((Status::Unlocked, 100, Box::new(tutorial::WinBattle)));
-        self.quests
-            .push((Status::Unlocked, 100, Box::new(tutorial::BuySword)));
-        self.quests
-            .push((Status::Unlocked, 100, Box::new(tutorial::UsePotion)));
-        self.quests
-            .push((Status::Unlocked, 100, Box::new(level::ReachLevel::new(2))));
+        self.quests.push((
+            Status::Unlocked,
+            Reward::gold(100),
+            Box::new(tutorial::WinBattle),
+        ));
+        self.quests.push((
+            Status::Unlocked,
+            Reward::gold(100),
+            Box::new(tutorial::BuySword),
+        ));
+        self.quests.push((
+            Status::Unlocked,
+            Reward::gold(100),
+            Box::new(tutorial::UsePotion),
+        ));
+        self.quests.push((
+            Status::Unlocked,
+            Reward::gold(100),
+            Box::new(level::ReachLevel::new(2)),
+        ));
 
-        self.quests
-            .push((Status::Locked(2), 200, Box::new(tutorial::FindChest)));
-        self.quests
-            .push((Status::Locked(2), 500, Box::new(level::ReachLevel::new(5))));
         self.quests.push((
             Status::Locked(2),
-            1000,
+            Reward::gold(200),
+            Box::new(tutorial::FindChest),
+        ));
+        self.quests.push((
+            Status::Locked(2),
+            Reward::gold(500),
+            Box::new(level::ReachLevel::new(5)),
+        ));
+        self.quests.push((
+            Status::Locked(2),
+            Reward::gold(1000),
             beat_enemy::of_class(class::Category::Common, "beat all common creatures"),
         ));
 
-        self.quests
-            .push((Status::Locked(5), 200, Box::new(tutorial::VisitTomb)));
-        self.quests
-            .push((Status::Locked(5), 300, Box::new(ring::EquipRing)));
         self.quests.push((
             Status::Locked(5),
-            1000,
+            Reward::gold(200),
+            Box::new(tutorial::VisitTomb),
+        ));
+        self.quests.push((
+            Status::Locked(5),
+            Reward::gold(300),
+            Box::new(ring::EquipRing),
+        ));
+        self.quests.push((
+            Status::Locked(5),
+            Reward::gold(1000),
             Box::new(level::ReachLevel::new(10)),
         ));
         self.quests.push((
             Status::Locked(5),
-            5000,
+            Reward::gold(5000),
             beat_enemy::of_class(class::Category::Rare, "beat all rare creatures"),
         ));
-        self.quests
-            .push((Status::Locked(5), 1000, beat_enemy::at_distance(10)));
+        self.quests.push((
+            Status::Locked(5),
+            Reward::gold(1000),
+            beat_enemy::at_distance(10),
+        ));
 
         self.quests.push((
             Status::Locked(10),
-            10000,
+            Reward::gold(10000),
             beat_enemy::of_class(class::Category::Legendary, "beat all legendary creatures"),
         ));
 
         self.quests.push((
             Status::Locked(10),
-            10000,
+            Reward::gold(10000),
             Box::new(level::ReachLevel::new(50)),
         ));
 
         for name in class::Class::names(class::Category::Player) {
             self.quests.push((
                 Status::Locked(10),
-                5000,
+                Reward::gold(5000),
                 Box::new(level::RaiseClassLevels::new(&name)),
             ));
         }
 
         self.quests.push((
             Status::Locked(15),
-            30000,
+            Reward::gold(30000),
             Box::new(ring::FindAllRings::new()),
         ));
+        self.quests.push((
+            Status::Locked(15),
+            Reward::gold(20000),
+            beat_enemy::shadow(),
+        ));
         self.quests
-            .push((Status::Locked(15), 20000, beat_enemy::shadow()));
-        self.quests
-            .push((Status::Locked(15), 20000, beat_enemy::dev()));
+            .push((Status::Locked(15), Reward::gold(20000), beat_enemy::dev()));
 
         self.quests.push((
             Status::Locked(50),
-            100000,
+            Reward::gold(100000),
             Box::new(level::ReachLevel::new(100)),
         ));
         self.quests
-            .push((Status::Locked(50), 1000000, ring::gorthaur()));
+            .push((Status::Locked(50), Reward::gold(1000000), ring::gorthaur()));
     }
 
     /// Pass the event to each of the quests, moving the completed ones to DONE.
-    /// The total gold reward is returned.
-    fn handle(&mut self, event: &Event) -> i32 {
+    /// Quests waiting for another one to be finished are skipped.
+    /// The description and reward of the completed quests are returned.
+    fn handle(&mut self, event: &Event) -> Vec<(String, Reward)> {
         self.unlock_quests(event);
 
-        let mut total_reward = 0;
+        let mut completed = Vec::new();
+        let mut ids = Vec::new();
 
         for (status, reward, quest) in &mut self.quests {
-            if let Status::Completed = status {
+            if let Status::Completed | Status::Requires(_) = status {
                 continue;
             }
 
             let is_done = quest.handle(event);
             if is_done {
-                total_reward += *reward;
-                log::quest_done(*reward);
-                *status = Status::Completed
+                *status = Status::Completed;
+                completed.push((quest.description(), reward.clone()));
+                if let Some(id) = quest.as_custom().and_then(|custom| custom.id()) {
+                    ids.push(id.to_string());
+                }
             }
         }
 
-        total_reward
+        self.unlock_followups(&ids);
+
+        for rotating in &mut self.rotating {
+            if rotating.status == Status::Unlocked && rotating.quest.handle(event) {
+                rotating.status = Status::Completed;
+                completed.push((rotating.quest.description(), rotating.reward.clone()));
+            }
+        }
+        completed
+    }
+
+    /// Replace the repeatable quests that expired by the given date with
+    /// the ones for the current period.
+    pub fn rotate(&mut self, today: chrono::NaiveDate) {
+        self.rotating.retain(|rotating| rotating.expires > today);
+        for period in &[Period::Daily, Period::Weekly] {
+            if !self.rotating.iter().any(|r| r.period == *period) {
+                self.rotating.push(rotation::pick(*period, today));
+            }
+        }
     }
 
     /// If the event is a level up, unlock quests for that level.
@@ -251,20 +500,76 @@ impl QuestList {
         }
     }
 
-    pub fn list(&self) -> Vec<(bool, String)> {
-        let mut result = Vec::new();
+    /// The status to show for the quest, referring to the one it requires
+    /// by description instead of id.
+    fn shown_status(&self, status: &Status) -> Status {
+        if let Status::Requires(id) = status {
+            let required = self.quests.iter().find_map(|(_, _, quest)| {
+                quest
+                    .as_custom()
+                    .filter(|custom| custom.id() == Some(id))
+                    .map(|custom| custom.description())
+            });
+            if let Some(description) = required {
+                return Status::Requires(description);
+            }
+        }
+        status.clone()
+    }
 
-        for (status, _, q) in &self.quests {
-            match status {
-                Status::Locked(_) => {}
-                Status::Unlocked => result.push((false, q.description())),
-                Status::Completed => result.push((true, q.description())),
-            };
+    /// Unlock the quests that were waiting for the given ones to be finished.
+    fn unlock_followups(&mut self, completed: &[String]) {
+        for (status, _, _) in &mut self.quests {
+            if let Status::Requires(required) = status {
+                if completed.contains(required) {
+                    *status = Status::Unlocked;
+                }
+            }
         }
-        result
+    }
+
+    /// The quests to show in the todo list. Locked quests are only
+    /// included if `all` is true.
+    pub fn list(&self, all: bool) -> Vec<Entry> {
+        self.quests
+            .iter()
+            .filter(|(status, _, _)| {
+                all || !matches!(status, Status::Locked(_) | Status::Requires(_))
+            })
+            .map(|(status, reward, quest)| Entry {
+                description: quest.description(),
+                status: self.shown_status(status),
+                reward: reward.clone(),
+                progress: quest.progress(),
+                chain: quest.chain().cloned(),
+                period: None,
+            })
+            .chain(self.rotating.iter().map(|rotating| Entry {
+                description: rotating.quest.description(),
+                status: rotating.status.clone(),
+                reward: rotating.reward.clone(),
+                progress: rotating.quest.progress(),
+                chain: None,
+                period: Some(rotating.period),
+            }))
+            .collect()
     }
 }
 
+/// A quest as shown in the todo list.
+pub struct Entry {
+    pub description: String,
+
+    /// The quests waiting for another one refer to it by description.
+    pub status: Status,
+    pub reward: Reward,
+    pub progress: Option<(i32, i32)>,
+    pub chain: Option<Chain>,
+
+    /// How often the quest is replaced, for the repeatable ones.
+    pub period: Option<Period>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -276,37 +581,45 @@ mod tests {
 
     #[test]
     fn test_quest_status() {
-        let mut quests = QuestList { quests: Vec::new() };
-        quests
-            .quests
-            .push((Status::Unlocked, 10, Box::new(level::ReachLevel::new(2))));
-        quests
-            .quests
-            .push((Status::Locked(2), 20, Box::new(level::ReachLevel::new(3))));
-        quests
-            .quests
-            .push((Status::Locked(3), 30, Box::new(level::ReachLevel::new(4))));
-        quests
-            .quests
-            .push((Status::Locked(4), 40, Box::new(level::ReachLevel::new(5))));
+        let mut quests = QuestList::default();
+        quests.quests.push((
+            Status::Unlocked,
+            Reward::gold(10),
+            Box::new(level::ReachLevel::new(2)),
+        ));
+        quests.quests.push((
+            Status::Locked(2),
+            Reward::gold(20),
+            Box::new(level::ReachLevel::new(3)),
+        ));
+        quests.quests.push((
+            Status::Locked(3),
+            Reward::gold(30),
+            Box::new(level::ReachLevel::new(4)),
+        ));
+        quests.quests.push((
+            Status::Locked(4),
+            Reward::gold(40),
+            Box::new(level::ReachLevel::new(5)),
+        ));
 
         assert_eq!(1, count_status(&quests, Status::Unlocked));
         assert_eq!(0, count_status(&quests, Status::Completed));
 
-        let reward = quests.handle(&Event::LevelUp {
+        let reward = total_gold(quests.handle(&Event::LevelUp {
             count: 1,
             current: 2,
             class: "warrior".to_string(),
-        });
+        }));
         assert_eq!(1, count_status(&quests, Status::Unlocked));
         assert_eq!(1, count_status(&quests, Status::Completed));
         assert_eq!(10, reward);
 
-        let reward = quests.handle(&Event::LevelUp {
+        let reward = total_gold(quests.handle(&Event::LevelUp {
             count: 2,
             current: 4,
             class: "warrior".to_string(),
-        });
+        }));
         assert_eq!(1, count_status(&quests, Status::Unlocked));
         assert_eq!(3, count_status(&quests, Status::Completed));
         assert_eq!(50, reward);
@@ -351,12 +664,232 @@ mod tests {
         assert_eq!(1, count_status(&game.quests, Status::Completed));
     }
 
+    #[test]
+    fn test_progress() {
+        let mut game = game::Game::new();
+        game.quests.quests = vec![
+            (
+                Status::Unlocked,
+                Reward::gold(10),
+                beat_enemy::of_class(class::Category::Common, "beat all common creatures"),
+            ),
+            (
+                Status::Locked(5),
+                Reward::gold(10),
+                Box::new(level::ReachLevel::new(10)),
+            ),
+        ];
+
+        let total = class::Class::names(class::Category::Common).len() as i32;
+        let list = game.quests.list(false);
+        assert_eq!(1, list.len());
+        assert_eq!(Some((0, total)), list[0].progress);
+
+        let enemy_class = class::Class::of(class::Category::Common)[0].clone();
+        battle_won(&mut game, &Character::new(enemy_class, 1), 0);
+        game.player.level = 3;
+        level_up(&mut game, 2);
+
+        let list = game.quests.list(true);
+        assert_eq!(2, list.len());
+        assert_eq!(Some((1, total)), list[0].progress);
+        assert_eq!(Status::Locked(5), list[1].status);
+        assert_eq!(Some((3, 10)), list[1].progress);
+    }
+
+    #[test]
+    fn test_game_events() {
+        let yaml = b"
+- description: go 2 steps away from home
+  event: visit
+  distance: 2
+  reward: 10
+- description: equip the void ring
+  event: ring_equip
+  item: void
+  reward: 10
+- description: unequip the void ring
+  event: ring_unequip
+  item: void
+  reward: 10
+";
+        let mut game = game::Game::new();
+        game.quests.quests = custom::Definition::parse(yaml)
+            .unwrap()
+            .iter()
+            .map(|def| def.compile())
+            .collect();
+
+        let home = Location::home().path_string();
+        game.visit(location_from(&format!("{}/a", home))).unwrap();
+        assert_eq!(0, count_status(&game.quests, Status::Completed));
+        game.visit(location_from(&format!("{}/a/b", home))).unwrap();
+        assert_eq!(1, count_status(&game.quests, Status::Completed));
+
+        game.add_item(Box::new(Ring::Void));
+        game.use_item(Key::Ring(Ring::Void)).unwrap();
+        assert_eq!(2, count_status(&game.quests, Status::Completed));
+        game.use_item(Key::Ring(Ring::Void)).unwrap();
+        assert_eq!(3, count_status(&game.quests, Status::Completed));
+    }
+
+    #[test]
+    fn test_sync() {
+        let yaml = b"
+- description: bribe two enemies
+  event: bribe
+  count: 2
+  reward: 10
+- description: open a chest
+  event: chest_found
+  reward: 10
+- description: run away
+  event: run_away
+  reward: 10
+";
+        let mut quests = QuestList::default();
+        quests.sync(Some(&custom::Definition::parse(yaml).unwrap()), 1);
+        quests.handle(&Event::Bribe { success: true });
+        quests.handle(&Event::ChestFound);
+
+        // the quests file was edited
+        let yaml = b"
+- description: bribe two enemies
+  event: bribe
+  count: 3
+  reward: 50
+- description: open a chest
+  event: chest_found
+  reward: 10
+- description: find a tombstone
+  event: tombstone_found
+  unlock_level: 2
+  reward: 10
+";
+        quests.sync(Some(&custom::Definition::parse(yaml).unwrap()), 3);
+        let list = quests.list(true);
+        assert_eq!(3, list.len());
+        assert_eq!(Some((1, 3)), list[0].progress);
+        assert_eq!(Reward::gold(50), list[0].reward);
+        assert_eq!(Status::Completed, list[1].status);
+        assert_eq!(Status::Unlocked, list[2].status);
+
+        // back to the default quests when the file is removed
+        quests.sync(None, 3);
+        assert_eq!(QuestList::new().quests.len(), quests.quests.len());
+        assert!(quests
+            .list(true)
+            .iter()
+            .all(|entry| entry.description != "open a chest"));
+    }
+
+    #[test]
+    fn test_rotate() {
+        let mut quests = QuestList::default();
+        let monday = chrono::NaiveDate::from_ymd_opt(2021, 8, 2).unwrap();
+        quests.rotate(monday);
+        assert_eq!(2, quests.list(false).len());
+
+        // completed quests stay until they expire
+        for rotating in &mut quests.rotating {
+            rotating.status = Status::Completed;
+        }
+        quests.rotate(monday);
+        assert_eq!(Status::Completed, quests.list(false)[0].status);
+
+        let tuesday = monday + chrono::Duration::days(1);
+        quests.rotate(tuesday);
+        let list = quests.list(false);
+        assert_eq!(Some(Period::Weekly), list[0].period);
+        assert_eq!(Status::Completed, list[0].status);
+        assert_eq!(Some(Period::Daily), list[1].period);
+        assert_eq!(Status::Unlocked, list[1].status);
+    }
+
+    #[test]
+    fn test_requires() {
+        let yaml = b"
+- id: first-level
+  description: raise a level
+  event: level_up
+  reward: 10
+- description: raise two more levels
+  event: level_up
+  count: 2
+  requires: first-level
+  reward: 20
+";
+        let mut quests = QuestList::default();
+        quests.sync(Some(&custom::Definition::parse(yaml).unwrap()), 1);
+        assert_eq!(1, quests.list(false).len());
+        assert_eq!(
+            Status::Requires(String::from("raise a level")),
+            quests.list(true)[1].status
+        );
+
+        // the followup doesn't get the event that unlocks it
+        let reward = total_gold(quests.handle(&Event::LevelUp {
+            count: 1,
+            current: 2,
+            class: "warrior".to_string(),
+        }));
+        assert_eq!(10, reward);
+        assert_eq!(Status::Unlocked, quests.quests[1].0);
+        assert_eq!(Some((0, 2)), quests.quests[1].2.progress());
+
+        // unlocked by id, even if the description changes
+        let yaml = b"
+- id: first-level
+  description: raise your first level
+  event: level_up
+  reward: 10
+- description: raise two more levels
+  event: level_up
+  count: 2
+  requires: first-level
+  reward: 20
+";
+        let mut quests = QuestList::default();
+        quests.sync(Some(&custom::Definition::parse(yaml).unwrap()), 1);
+        quests.quests[0].0 = Status::Completed;
+        quests.sync(Some(&custom::Definition::parse(yaml).unwrap()), 2);
+        assert_eq!(Status::Unlocked, quests.quests[1].0);
+    }
+
+    #[test]
+    fn default_chains() {
+        let quests = QuestList::new();
+        let chained = quests
+            .list(true)
+            .into_iter()
+            .filter(|entry| entry.chain.is_some())
+            .count();
+        assert_eq!(CHAINS.len(), chained);
+        assert!(chained > 1);
+
+        // sync keeps the built-in quests and their progress
+        let mut game = game::Game::new();
+        battle_won(&mut game, &Character::player(), 0);
+        let before = count_status(&game.quests, Status::Completed);
+        game.quests.refresh(1);
+        assert_eq!(quests.quests.len(), game.quests.quests.len());
+        assert_eq!(before, count_status(&game.quests, Status::Completed));
+    }
+
     #[test]
     fn test_level_up() {
         let mut game = game::Game::new();
         game.quests.quests = vec![
-            (Status::Unlocked, 10, Box::new(level::ReachLevel::new(2))),
-            (Status::Unlocked, 10, Box::new(level::ReachLevel::new(3))),
+            (
+                Status::Unlocked,
+                Reward::gold(10),
+                Box::new(level::ReachLevel::new(2)),
+            ),
+            (
+                Status::Unlocked,
+                Reward::gold(10),
+                Box::new(level::ReachLevel::new(3)),
+            ),
         ];
 
         game.player.level = 2;
@@ -374,7 +907,7 @@ mod tests {
     #[test]
     fn equip_ring() {
         let mut game = game::Game::new();
-        game.quests.quests = vec![(Status::Unlocked, 1, Box::new(ring::EquipRing))];
+        game.quests.quests = vec![(Status::Unlocked, Reward::gold(1), Box::new(ring::EquipRing))];
 
         game.add_item(Box::new(item::ring::Ring::Void));
         game.use_item(Key::Ring(item::ring::Ring::Void)).unwrap();
@@ -385,7 +918,11 @@ mod tests {
     #[test]
     fn find_all_rings() {
         let mut game = game::Game::new();
-        game.quests.quests = vec![(Status::Unlocked, 1, Box::new(ring::FindAllRings::new()))];
+        game.quests.quests = vec![(
+            Status::Unlocked,
+            Reward::gold(1),
+            Box::new(ring::FindAllRings::new()),
+        )];
 
         for ring in item::ring::Ring::set() {
             game.add_item(Box::new(ring));
@@ -397,7 +934,7 @@ mod tests {
     #[test]
     fn gorthaur() {
         let mut game = game::Game::new();
-        game.quests.quests = vec![(Status::Unlocked, 1, ring::gorthaur())];
+        game.quests.quests = vec![(Status::Unlocked, Reward::gold(1), ring::gorthaur())];
 
         // fake a +100 distance location
         let mut fake_path = String::from("~");
@@ -422,6 +959,10 @@ mod tests {
         assert_eq!(Status::Completed, game.quests.quests[0].0);
     }
 
+    fn total_gold(rewards: Vec<(String, Reward)>) -> i32 {
+        rewards.iter().map(|(_, reward)| reward.gold).sum()
+    }
+
     fn count_status(quests: &QuestList, status: Status) -> usize {
         quests
             .quests
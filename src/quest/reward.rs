@@ -0,0 +1,206 @@
+use crate::game::Game;
+use crate::item::equipment::Equipment;
+use crate::item::key::Key;
+use crate::item::{stone, Escape, Ether, Item, Potion, Remedy};
+use anyhow::{bail, Result};
+use core::fmt;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// What the player gets for finishing a quest.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Reward {
+    pub gold: i32,
+    pub xp: i32,
+
+    /// Items added to the inventory. Potions and ethers match the player
+    /// level and rings are only granted if they haven't been found yet.
+    pub items: Vec<Key>,
+
+    /// Level of a sword or shield to equip, if it's an upgrade.
+    pub sword: Option<i32>,
+    pub shield: Option<i32>,
+
+    /// A ring to make available at the shop.
+    pub unlocks: Option<Key>,
+}
+
+impl Reward {
+    pub fn gold(gold: i32) -> Self {
+        Self {
+            gold,
+            ..Self::default()
+        }
+    }
+
+    /// Verify that the reward items can be granted.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(key) = self
+            .items
+            .iter()
+            .find(|k| matches!(k, Key::Sword | Key::Shield))
+        {
+            bail!(
+                "{} rewards should set a level instead of being an item",
+                key
+            );
+        }
+        if let Some(key) = &self.unlocks {
+            if !matches!(key, Key::Ring(_)) {
+                bail!("only rings can be unlocked at the shop, not {}", key);
+            }
+        }
+        Ok(())
+    }
+
+    /// Add the gold, items and equipment of the reward to the game and
+    /// return the part of it that could actually be granted.
+    /// The xp is left to the caller, since raising levels may in turn
+    /// complete other quests.
+    pub fn grant(&self, game: &mut Game) -> Self {
+        let mut granted = Self {
+            gold: self.gold,
+            xp: self.xp,
+            unlocks: self.unlocks.clone(),
+            ..Self::default()
+        };
+
+        game.gold += self.gold;
+        game.stats.gold_earned += self.gold;
+
+        for key in &self.items {
+            if let Some(item) = item(key, game) {
+                game.add_item(item);
+                granted.items.push(key.clone());
+            }
+        }
+
+        if let Some(level) = self.sword {
+            let sword = Equipment::sword(level);
+            if sword.is_upgrade_from(&game.player.sword) {
+                game.player.sword = Some(sword);
+                granted.sword = Some(level);
+            }
+        }
+        if let Some(level) = self.shield {
+            let shield = Equipment::shield(level);
+            if shield.is_upgrade_from(&game.player.shield) {
+                game.player.shield = Some(shield);
+                granted.shield = Some(level);
+            }
+        }
+
+        if let Some(key) = &self.unlocks {
+            game.shop_unlocks.insert(key.clone());
+        }
+        granted
+    }
+}
+
+/// Build the item for the given key, taking rings out of the ring pool.
+fn item(key: &Key, game: &mut Game) -> Option<Box<dyn Item>> {
+    let level = game.player.rounded_level();
+    let item: Box<dyn Item> = match key {
+        Key::Potion => Box::new(Potion::new(level)),
+        Key::Ether => Box::new(Ether::new(level)),
+        Key::Remedy => Box::new(Remedy::new()),
+        Key::Escape => Box::new(Escape::new()),
+        Key::HealthStone => Box::new(stone::Health),
+        Key::MagicStone => Box::new(stone::Magic),
+        Key::PowerStone => Box::new(stone::Power),
+        Key::SpeedStone => Box::new(stone::Speed),
+        Key::LevelStone => Box::new(stone::Level),
+        Key::Ring(ring) => Box::new(game.ring_pool.take(ring)?),
+        Key::Sword | Key::Shield => return None,
+    };
+    Some(item)
+}
+
+impl fmt::Display for Reward {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if self.gold != 0 {
+            parts.push(format!("{:+}g", self.gold));
+        }
+        if self.xp != 0 {
+            parts.push(format!("{:+}xp", self.xp));
+        }
+
+        let mut counts = BTreeMap::new();
+        for key in &self.items {
+            *counts.entry(key.to_string()).or_insert(0) += 1;
+        }
+        for (key, count) in counts {
+            parts.push(format!("+{}x{}", key, count));
+        }
+
+        if let Some(level) = self.sword {
+            parts.push(format!("+sword[{}]", level));
+        }
+        if let Some(level) = self.shield {
+            parts.push(format!("+shield[{}]", level));
+        }
+        if let Some(key) = &self.unlocks {
+            parts.push(format!("+{}@shop", key));
+        }
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::ring::Ring;
+
+    #[test]
+    fn grant() {
+        let mut game = Game::new();
+        game.ring_pool.remove(&Ring::Void);
+        game.player.sword = Some(Equipment::sword(10));
+
+        let reward = Reward {
+            gold: 100,
+            xp: 50,
+            items: vec![
+                Key::Potion,
+                Key::Potion,
+                Key::Ring(Ring::Void),
+                Key::Ring(Ring::Gold),
+            ],
+            sword: Some(5),
+            shield: Some(5),
+            unlocks: Some(Key::Ring(Ring::Diamond)),
+        };
+        assert_eq!(
+            "+100g +50xp +gold-rngx1 +potionx2 +void-rngx1 +sword[5] +shield[5] +diamond-rng@shop",
+            reward.to_string()
+        );
+
+        let granted = reward.grant(&mut game);
+        assert_eq!(100, game.gold);
+        assert_eq!(2, game.inventory()[&Key::Potion]);
+        assert!(!game.ring_pool.contains(&Ring::Gold));
+        assert!(game.shop_unlocks.contains(&Key::Ring(Ring::Diamond)));
+        assert_eq!(5, game.player.shield.as_ref().unwrap().level());
+
+        // the void ring was already found and the sword isn't an upgrade
+        assert_eq!(
+            "+100g +50xp +gold-rngx1 +potionx2 +shield[5] +diamond-rng@shop",
+            granted.to_string()
+        );
+    }
+
+    #[test]
+    fn validate() {
+        assert!(Reward::gold(10).validate().is_ok());
+
+        let mut reward = Reward::gold(10);
+        reward.items.push(Key::Sword);
+        assert!(reward.validate().is_err());
+
+        let mut reward = Reward::gold(10);
+        reward.unlocks = Some(Key::Potion);
+        assert!(reward.validate().is_err());
+    }
+}
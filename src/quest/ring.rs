@@ -24,6 +24,7 @@ impl Quest for EquipRing {
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FindAllRings {
+    #[serde(serialize_with = "crate::datafile::sorted")]
     to_find: HashSet<Ring>,
 }
 
@@ -38,9 +39,13 @@ impl FindAllRings {
 #[typetag::serde]
 impl Quest for FindAllRings {
     fn description(&self) -> String {
+        String::from("find all rings")
+    }
+
+    fn progress(&self) -> Option<(i32, i32)> {
         let total = Ring::set().len();
         let already_found = total - self.to_find.len();
-        format!("find all rings {}/{}", already_found, total)
+        Some((already_found as i32, total as i32))
     }
 
     fn handle(&mut self, event: &Event) -> bool {
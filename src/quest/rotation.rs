@@ -0,0 +1,105 @@
+use super::custom::Definition;
+use super::{Quest, Reward, Status};
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use strum_macros::Display;
+
+/// How often a repeatable quest is replaced by a new one.
+#[derive(Serialize, Deserialize, Display, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Period {
+    Daily,
+    Weekly,
+}
+
+/// A repeatable quest, active until its expiry date.
+#[derive(Serialize, Deserialize)]
+pub struct Rotating {
+    pub period: Period,
+
+    /// The first day the quest is no longer active.
+    pub expires: NaiveDate,
+
+    pub status: Status,
+    pub reward: Reward,
+    pub quest: Box<dyn Quest>,
+}
+
+#[derive(Deserialize)]
+struct Pools {
+    daily: Vec<Value>,
+    weekly: Vec<Value>,
+}
+
+static DAILY: Lazy<Vec<Definition>> = Lazy::new(|| pool(|pools| pools.daily));
+static WEEKLY: Lazy<Vec<Definition>> = Lazy::new(|| pool(|pools| pools.weekly));
+
+fn pool(period: fn(Pools) -> Vec<Value>) -> Vec<Definition> {
+    let pools: Pools = serde_yaml::from_slice(include_bytes!("rotation.yaml")).unwrap();
+    Definition::from_entries(period(pools)).unwrap()
+}
+
+/// The current local date, which determines the active repeatable quests.
+pub fn today() -> NaiveDate {
+    Local::now().naive_local().date()
+}
+
+/// The quest of the given period active on the given date. It only depends
+/// on the date, so every player gets the same ones.
+pub fn pick(period: Period, today: NaiveDate) -> Rotating {
+    let (start, days, pool) = match period {
+        Period::Daily => (today, 1, &*DAILY),
+        Period::Weekly => {
+            let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+            (monday, 7, &*WEEKLY)
+        }
+    };
+
+    let index = (start.num_days_from_ce() / days) as usize % pool.len();
+    let (_, reward, quest) = pool[index].compile();
+    Rotating {
+        period,
+        expires: start + Duration::days(days as i64),
+        status: Status::Unlocked,
+        reward,
+        quest,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn pick_by_date() {
+        let monday = date(2021, 8, 2);
+        let sunday = date(2021, 8, 8);
+
+        let daily = pick(Period::Daily, monday);
+        assert_eq!(date(2021, 8, 3), daily.expires);
+        assert_eq!(
+            daily.quest.description(),
+            pick(Period::Daily, monday).quest.description()
+        );
+        assert_ne!(
+            daily.quest.description(),
+            pick(Period::Daily, monday + Duration::days(1))
+                .quest
+                .description()
+        );
+
+        let weekly = pick(Period::Weekly, sunday);
+        assert_eq!(date(2021, 8, 9), weekly.expires);
+        assert_eq!(
+            weekly.quest.description(),
+            pick(Period::Weekly, monday).quest.description()
+        );
+    }
+}
@@ -2,8 +2,34 @@
 
 use crate::character::StatusEffect;
 use crate::location;
-use rand::Rng;
-use std::cmp::max;
+use once_cell::sync::OnceCell;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::{max, min};
+use std::sync::{Mutex, MutexGuard};
+
+// A single random number generator is shared by the whole game, so that
+// seeding it makes a given command sequence fully reproducible.
+static RNG: OnceCell<Mutex<StdRng>> = OnceCell::new();
+
+/// Set up the game's random number generator. If a seed is given, every
+/// random outcome of the command will be the same across runs with that seed.
+/// Calling it again replaces the generator with a newly seeded one.
+pub fn init(seed: Option<u64>) {
+    let rng = seed.map_or_else(StdRng::from_entropy, StdRng::seed_from_u64);
+    if let Err(new) = RNG.set(Mutex::new(rng)) {
+        *self::rng() = new.into_inner().unwrap();
+    }
+}
+
+/// Get exclusive access to the shared random number generator.
+/// Intended for code that needs to pass an rng to the rand crate helpers,
+/// e.g. to choose a random element of a collection.
+pub fn rng() -> MutexGuard<'static, StdRng> {
+    RNG.get_or_init(|| Mutex::new(StdRng::from_entropy()))
+        .lock()
+        .unwrap()
+}
 
 /// This trait exposes functions to deal with any element of the game that
 /// needs to incorporate randomness.
@@ -30,6 +56,8 @@ pub trait Randomizer {
 
     fn is_critical(&self) -> bool;
 
+    fn status_miss(&self, chance: u32) -> bool;
+
     fn counter_attack(&self) -> bool;
 
     fn inflicted(&self, status: Option<(StatusEffect, u32)>) -> Option<StatusEffect>;
@@ -62,7 +90,7 @@ pub struct DefaultRandomizer;
 
 impl Randomizer for DefaultRandomizer {
     fn should_enemy_appear(&self, distance: &location::Distance) -> bool {
-        let mut rng = rand::thread_rng();
+        let mut rng = rng();
 
         match distance {
             location::Distance::Near(_) => rng.gen_ratio(1, 3),
@@ -72,7 +100,7 @@ impl Randomizer for DefaultRandomizer {
     }
 
     fn bribe_succeeds(&self) -> bool {
-        let mut rng = rand::thread_rng();
+        let mut rng = rng();
         rng.gen_ratio(1, 2)
     }
 
@@ -87,12 +115,12 @@ impl Randomizer for DefaultRandomizer {
 
         let speed_contrib = if player_speed > enemy_speed { 2 } else { 0 };
 
-        let mut rng = rand::thread_rng();
+        let mut rng = rng();
         rng.gen_ratio(1 + level_contrib + speed_contrib, 5)
     }
 
     fn enemy_level(&self, level: i32) -> i32 {
-        let mut rng = rand::thread_rng();
+        let mut rng = rng();
         max(1, level + rng.gen_range(-4..5))
     }
 
@@ -100,7 +128,7 @@ impl Randomizer for DefaultRandomizer {
     fn damage(&self, value: i32) -> i32 {
         let value = value as f64;
 
-        let mut rng = rand::thread_rng();
+        let mut rng = rng();
         let min_val = (value * 0.8).floor() as i32;
         let max_val = (value * 1.2).ceil() as i32;
         max(1, rng.gen_range(min_val..=max_val))
@@ -110,25 +138,33 @@ impl Randomizer for DefaultRandomizer {
         if receiver_speed > attacker_speed {
             let ratio = receiver_speed / attacker_speed;
             let ratio = max(1, 5 - ratio) as u32;
-            let mut rng = rand::thread_rng();
+            let mut rng = rng();
             return rng.gen_ratio(1, ratio);
         }
         false
     }
 
     fn is_critical(&self) -> bool {
-        let mut rng = rand::thread_rng();
+        let mut rng = rng();
         rng.gen_ratio(1, 20)
     }
 
+    fn status_miss(&self, chance: u32) -> bool {
+        if chance == 0 {
+            return false;
+        }
+        let mut rng = rng();
+        rng.gen_ratio(min(chance, 100), 100)
+    }
+
     fn counter_attack(&self) -> bool {
-        let mut rng = rand::thread_rng();
+        let mut rng = rng();
         rng.gen_ratio(1, 2)
     }
 
     fn inflicted(&self, status: Option<(StatusEffect, u32)>) -> Option<StatusEffect> {
         if let Some((status, ratio)) = status {
-            let mut rng = rand::thread_rng();
+            let mut rng = rng();
             if rng.gen_ratio(1, ratio) {
                 return Some(status);
             }
@@ -137,7 +173,7 @@ impl Randomizer for DefaultRandomizer {
     }
 
     fn gold_gained(&self, base: i32) -> i32 {
-        let mut rng = rand::thread_rng();
+        let mut rng = rng();
         let min = (base as f64 * 0.6) as i32;
         let max = (base as f64 * 1.3) as i32;
         rng.gen_range(min..=max)
@@ -147,17 +183,17 @@ impl Randomizer for DefaultRandomizer {
         let min_value = max(1, increase / 2);
         let max_value = 3 * increase / 2;
 
-        let mut rng = rand::thread_rng();
+        let mut rng = rng();
         rng.gen_range(min_value..=max_value)
     }
 
     fn range(&self, max: i32) -> i32 {
-        let mut rng = rand::thread_rng();
+        let mut rng = rng();
         rng.gen_range(0..max)
     }
 
     fn gold_chest(&self, distance: &location::Distance) -> bool {
-        let mut rng = rand::thread_rng();
+        let mut rng = rng();
 
         match distance {
             location::Distance::Near(_) => rng.gen_ratio(6, 30),
@@ -167,7 +203,7 @@ impl Randomizer for DefaultRandomizer {
     }
 
     fn equipment_chest(&self, distance: &location::Distance) -> bool {
-        let mut rng = rand::thread_rng();
+        let mut rng = rng();
 
         match distance {
             location::Distance::Near(_) => rng.gen_ratio(1, 30),
@@ -177,7 +213,7 @@ impl Randomizer for DefaultRandomizer {
     }
 
     fn ring_chest(&self, distance: &location::Distance) -> bool {
-        let mut rng = rand::thread_rng();
+        let mut rng = rng();
 
         match distance {
             location::Distance::Near(_) => false,
@@ -187,7 +223,7 @@ impl Randomizer for DefaultRandomizer {
     }
 
     fn item_chest(&self, distance: &location::Distance) -> bool {
-        let mut rng = rand::thread_rng();
+        let mut rng = rng();
 
         match distance {
             location::Distance::Near(_) => rng.gen_ratio(1, 50),
@@ -236,6 +272,10 @@ impl Randomizer for TestRandomizer {
         false
     }
 
+    fn status_miss(&self, _chance: u32) -> bool {
+        false
+    }
+
     fn counter_attack(&self) -> bool {
         true
     }
@@ -277,6 +317,20 @@ impl Randomizer for TestRandomizer {
 mod tests {
     use super::*;
 
+    #[test]
+    fn init_twice() {
+        let sample = |seed| {
+            init(Some(seed));
+            let mut rng = rng();
+            (0..10).map(|_| rng.gen()).collect::<Vec<u64>>()
+        };
+
+        // seeding again starts the same sequence over
+        let first = sample(42);
+        assert_eq!(first, sample(42));
+        assert_ne!(first, sample(43));
+    }
+
     #[test]
     fn test_increase_stat() {
         let rand = DefaultRandomizer {};
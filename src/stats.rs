@@ -0,0 +1,73 @@
+use crate::character::Character;
+use crate::location::Location;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Lifetime statistics of the game, accumulated across all the heroes.
+/// Like quests, these are preserved on reset and only cleared by a hard reset.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct Stats {
+    pub battles: i32,
+    pub victories: i32,
+
+    /// Amount of enemies beaten, by class name.
+    pub enemies_beaten: HashMap<String, i32>,
+
+    /// Gold obtained from battles, chests and quests.
+    pub gold_earned: i32,
+
+    /// Farthest distance from home reached.
+    pub deepest: i32,
+
+    pub deaths: i32,
+
+    /// Amount of deaths by enemy class or status effect.
+    pub death_causes: HashMap<String, i32>,
+}
+
+impl Stats {
+    pub fn battle_won(&mut self, enemy: &Character, gold: i32) {
+        self.victories += 1;
+        self.gold_earned += gold;
+        *self.enemies_beaten.entry(enemy.name()).or_insert(0) += 1;
+    }
+
+    pub fn died(&mut self, cause: &str) {
+        self.deaths += 1;
+        *self.death_causes.entry(cause.to_string()).or_insert(0) += 1;
+    }
+
+    pub fn visit(&mut self, location: &Location) {
+        self.deepest = self.deepest.max(location.distance_from_home().len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::class::Class;
+    use crate::location::tests::location_from;
+
+    #[test]
+    fn record() {
+        let mut stats = Stats::default();
+        let enemy = Character::new(Class::player_first().clone(), 1);
+
+        stats.battle_won(&enemy, 10);
+        stats.battle_won(&enemy, 20);
+        stats.died("poison");
+        stats.died(&enemy.name());
+
+        assert_eq!(2, stats.victories);
+        assert_eq!(30, stats.gold_earned);
+        assert_eq!(2, stats.enemies_beaten[&enemy.name()]);
+        assert_eq!(2, stats.deaths);
+        assert_eq!(1, stats.death_causes["poison"]);
+
+        let home = Location::home();
+        stats.visit(&location_from(&format!("{}/a/b", home.path_string())));
+        stats.visit(&home);
+        assert_eq!(2, stats.deepest);
+    }
+}